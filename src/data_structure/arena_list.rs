@@ -0,0 +1,567 @@
+use cargo_snippet::snippet;
+use crate::data_structure::list::List;
+
+#[snippet("r3yohei_arena_list")]
+use std::{
+    fmt::Debug,
+    iter::FromIterator,
+    marker::PhantomData,
+    ops::{Index, IndexMut},
+};
+
+/// ArenaListのノードを指すハンドル．要素を削除するまで同じノードを指し続ける
+#[snippet("r3yohei_arena_list")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(usize);
+
+#[snippet("r3yohei_arena_list")]
+#[derive(Clone, Debug)]
+struct ArenaNode<T> {
+    value: Option<T>,
+    prev: usize,
+    next: usize,
+}
+
+/// Vecをアリーナにした双方向連結リスト
+/// 0番のノードを番兵にした環状リストで，NodeIdを使った挿入･削除･付け替えがO(1)
+/// 削除したノードの場所は使い回すので，削除済みのNodeIdは使わないこと
+#[snippet("r3yohei_arena_list")]
+#[derive(Clone)]
+pub struct ArenaList<T> {
+    nodes: Vec<ArenaNode<T>>,
+    free: Vec<usize>,
+    len: usize,
+}
+#[snippet("r3yohei_arena_list")]
+impl<T> ArenaList<T> {
+    pub fn new() -> Self {
+        Self {
+            nodes: vec![ArenaNode { value: None, prev: 0, next: 0 }],
+            free: vec![],
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn alloc(&mut self, x: T) -> usize {
+        let node = ArenaNode { value: Some(x), prev: 0, next: 0 };
+        match self.free.pop() {
+            Some(v) => {
+                self.nodes[v] = node;
+                v
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    // vをposの後ろにつなぐ
+    fn link_after(&mut self, pos: usize, v: usize) {
+        let next = self.nodes[pos].next;
+        self.nodes[v].prev = pos;
+        self.nodes[v].next = next;
+        self.nodes[next].prev = v;
+        self.nodes[pos].next = v;
+    }
+
+    // vを前後から外す
+    fn unlink(&mut self, v: usize) {
+        let (prev, next) = (self.nodes[v].prev, self.nodes[v].next);
+        self.nodes[prev].next = next;
+        self.nodes[next].prev = prev;
+    }
+
+    // 番兵(0)ならNone
+    fn to_id(v: usize) -> Option<NodeId> {
+        if v == 0 { None } else { Some(NodeId(v)) }
+    }
+
+    pub fn front_id(&self) -> Option<NodeId> {
+        Self::to_id(self.nodes[0].next)
+    }
+
+    pub fn back_id(&self) -> Option<NodeId> {
+        Self::to_id(self.nodes[0].prev)
+    }
+
+    pub fn next_id(&self, id: NodeId) -> Option<NodeId> {
+        Self::to_id(self.nodes[id.0].next)
+    }
+
+    pub fn prev_id(&self, id: NodeId) -> Option<NodeId> {
+        Self::to_id(self.nodes[id.0].prev)
+    }
+
+    /// 先頭からi番目(0始まり)のノード O(min(i, N-i))
+    pub fn nth_id(&self, i: usize) -> Option<NodeId> {
+        if i >= self.len {
+            return None;
+        }
+        let mut v = 0;
+        if i < self.len / 2 {
+            for _ in 0..=i {
+                v = self.nodes[v].next;
+            }
+        } else {
+            for _ in i..self.len {
+                v = self.nodes[v].prev;
+            }
+        }
+        Some(NodeId(v))
+    }
+
+    pub fn get(&self, id: NodeId) -> Option<&T> {
+        self.nodes.get(id.0).and_then(|node| node.value.as_ref())
+    }
+
+    pub fn get_mut(&mut self, id: NodeId) -> Option<&mut T> {
+        self.nodes.get_mut(id.0).and_then(|node| node.value.as_mut())
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.nodes[self.nodes[0].next].value.as_ref()
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        self.nodes[self.nodes[0].prev].value.as_ref()
+    }
+
+    /// idの後ろにxを入れる．idがNoneなら先頭に入れる
+    pub fn insert_after(&mut self, id: Option<NodeId>, x: T) -> NodeId {
+        let v = self.alloc(x);
+        self.link_after(id.map_or(0, |id| id.0), v);
+        self.len += 1;
+        NodeId(v)
+    }
+
+    /// idの前にxを入れる．idがNoneなら末尾に入れる
+    pub fn insert_before(&mut self, id: Option<NodeId>, x: T) -> NodeId {
+        let pos = self.nodes[id.map_or(0, |id| id.0)].prev;
+        let v = self.alloc(x);
+        self.link_after(pos, v);
+        self.len += 1;
+        NodeId(v)
+    }
+
+    pub fn push_front(&mut self, x: T) -> NodeId {
+        self.insert_after(None, x)
+    }
+
+    pub fn push_back(&mut self, x: T) -> NodeId {
+        self.insert_before(None, x)
+    }
+
+    /// idのノードを削除して値を返す
+    pub fn remove(&mut self, id: NodeId) -> T {
+        let x = self.nodes[id.0].value.take().expect("removed node");
+        self.unlink(id.0);
+        self.free.push(id.0);
+        self.len -= 1;
+        x
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.front_id().map(|id| self.remove(id))
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        self.back_id().map(|id| self.remove(id))
+    }
+
+    /// firstからlastまで(前から順に並んでいること)をdestの後ろに付け替える O(1)
+    /// destがNoneなら先頭に移す．destは付け替える範囲に含まれないこと
+    pub fn splice_after(&mut self, first: NodeId, last: NodeId, dest: Option<NodeId>) {
+        let (prev, next) = (self.nodes[first.0].prev, self.nodes[last.0].next);
+        self.nodes[prev].next = next;
+        self.nodes[next].prev = prev;
+        let pos = dest.map_or(0, |id| id.0);
+        let after = self.nodes[pos].next;
+        self.nodes[pos].next = first.0;
+        self.nodes[first.0].prev = pos;
+        self.nodes[last.0].next = after;
+        self.nodes[after].prev = last.0;
+    }
+
+    /// otherの要素をすべて末尾に移す．otherのNodeIdは使えなくなる O(otherの長さ)
+    pub fn append(&mut self, other: &mut Self) {
+        while let Some(x) = other.pop_front() {
+            self.push_back(x);
+        }
+    }
+
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T> {
+        let cur = self.nodes[0].next;
+        CursorMut { list: self, cur }
+    }
+
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, T> {
+        let cur = self.nodes[0].prev;
+        CursorMut { list: self, cur }
+    }
+
+    pub fn cursor_mut(&mut self, id: NodeId) -> CursorMut<'_, T> {
+        CursorMut { list: self, cur: id.0 }
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            list: self,
+            front: self.nodes[0].next,
+            back: self.nodes[0].prev,
+            len: self.len,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            front: self.nodes[0].next,
+            back: self.nodes[0].prev,
+            len: self.len,
+            nodes: self.nodes.as_mut_ptr(),
+            _marker: PhantomData,
+        }
+    }
+}
+#[snippet("r3yohei_arena_list")]
+impl<T> Default for ArenaList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+#[snippet("r3yohei_arena_list")]
+impl<T: Debug> Debug for ArenaList<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+#[snippet("r3yohei_arena_list")]
+impl<T> FromIterator<T> for ArenaList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        for x in iter {
+            list.push_back(x);
+        }
+        list
+    }
+}
+#[snippet("r3yohei_arena_list")]
+impl<T> Index<NodeId> for ArenaList<T> {
+    type Output = T;
+
+    fn index(&self, id: NodeId) -> &Self::Output {
+        self.get(id).expect("removed node")
+    }
+}
+#[snippet("r3yohei_arena_list")]
+impl<T> IndexMut<NodeId> for ArenaList<T> {
+    fn index_mut(&mut self, id: NodeId) -> &mut Self::Output {
+        self.get_mut(id).expect("removed node")
+    }
+}
+
+/// ArenaList上を動くカーソル
+/// 末尾の次(先頭の前)には要素のない位置があり，そこではcurrent()がNoneになる
+#[snippet("r3yohei_arena_list")]
+pub struct CursorMut<'a, T> {
+    list: &'a mut ArenaList<T>,
+    cur: usize,
+}
+#[snippet("r3yohei_arena_list")]
+impl<'a, T> CursorMut<'a, T> {
+    pub fn id(&self) -> Option<NodeId> {
+        ArenaList::<T>::to_id(self.cur)
+    }
+
+    pub fn current(&mut self) -> Option<&mut T> {
+        self.list.nodes[self.cur].value.as_mut()
+    }
+
+    pub fn move_next(&mut self) {
+        self.cur = self.list.nodes[self.cur].next;
+    }
+
+    pub fn move_prev(&mut self) {
+        self.cur = self.list.nodes[self.cur].prev;
+    }
+
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        let next = self.list.nodes[self.cur].next;
+        self.list.nodes[next].value.as_mut()
+    }
+
+    pub fn peek_prev(&mut self) -> Option<&mut T> {
+        let prev = self.list.nodes[self.cur].prev;
+        self.list.nodes[prev].value.as_mut()
+    }
+
+    /// 今の位置の後ろにxを入れる．カーソルは動かない
+    pub fn insert_after(&mut self, x: T) -> NodeId {
+        let id = self.id();
+        self.list.insert_after(id, x)
+    }
+
+    /// 今の位置の前にxを入れる．カーソルは動かない
+    pub fn insert_before(&mut self, x: T) -> NodeId {
+        let id = self.id();
+        self.list.insert_before(id, x)
+    }
+
+    /// 今の位置の要素を削除し，カーソルを次に進める
+    pub fn remove_current(&mut self) -> Option<T> {
+        let id = self.id()?;
+        self.cur = self.list.nodes[self.cur].next;
+        Some(self.list.remove(id))
+    }
+}
+
+#[snippet("r3yohei_arena_list")]
+pub struct Iter<'a, T> {
+    list: &'a ArenaList<T>,
+    front: usize,
+    back: usize,
+    len: usize,
+}
+#[snippet("r3yohei_arena_list")]
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        let v = self.front;
+        self.front = self.list.nodes[v].next;
+        self.list.nodes[v].value.as_ref()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+#[snippet("r3yohei_arena_list")]
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        let v = self.back;
+        self.back = self.list.nodes[v].prev;
+        self.list.nodes[v].value.as_ref()
+    }
+}
+#[snippet("r3yohei_arena_list")]
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+/// 異なるノードへの&mutを同時に返すので，アリーナの先頭を生ポインタで持つ
+#[snippet("r3yohei_arena_list")]
+pub struct IterMut<'a, T> {
+    nodes: *mut ArenaNode<T>,
+    front: usize,
+    back: usize,
+    len: usize,
+    _marker: PhantomData<&'a mut T>,
+}
+#[snippet("r3yohei_arena_list")]
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        unsafe {
+            let node = self.nodes.add(self.front);
+            self.front = (*node).next;
+            (*node).value.as_mut()
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+#[snippet("r3yohei_arena_list")]
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        unsafe {
+            let node = self.nodes.add(self.back);
+            self.back = (*node).prev;
+            (*node).value.as_mut()
+        }
+    }
+}
+#[snippet("r3yohei_arena_list")]
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {}
+#[snippet("r3yohei_arena_list")]
+impl<'a, T> IntoIterator for &'a ArenaList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+#[snippet("r3yohei_arena_list")]
+impl<'a, T> IntoIterator for &'a mut ArenaList<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+#[snippet("r3yohei_arena_list")]
+#[snippet(include = "r3yohei_list")]
+impl<T: Clone> List<T> for ArenaList<T> {
+    fn size(&self) -> usize {
+        self.len
+    }
+    fn get(&self, i: usize) -> Option<T> {
+        self.nth_id(i).map(|id| self[id].clone())
+    }
+    fn set(&mut self, i: usize, x: T) -> Option<T> {
+        self.nth_id(i).map(|id| std::mem::replace(&mut self[id], x))
+    }
+    fn add(&mut self, i: usize, x: T) {
+        let id = self.nth_id(i);
+        self.insert_before(id, x);
+    }
+    fn remove(&mut self, i: usize) -> Option<T> {
+        self.nth_id(i).map(|id| ArenaList::remove(self, id))
+    }
+    fn visit(&self, f: &mut dyn FnMut(&T) -> bool) {
+        for x in self.iter() {
+            if !f(x) {
+                break;
+            }
+        }
+    }
+    fn add_all(&mut self, i: usize, xs: Vec<T>) {
+        let id = self.nth_id(i);
+        for x in xs {
+            self.insert_before(id, x);
+        }
+    }
+    fn remove_range(&mut self, l: usize, r: usize) -> Vec<T> {
+        let mut id = self.nth_id(l);
+        let mut res = vec![];
+        for _ in l..r {
+            let crt = id.unwrap();
+            id = self.next_id(crt);
+            res.push(ArenaList::remove(self, crt));
+        }
+        res
+    }
+}
+
+#[test]
+fn test_arena_list() {
+    let mut list = ArenaList::new();
+    let b = list.push_back('b');
+    let a = list.push_front('a');
+    let d = list.insert_after(Some(b), 'd');
+    let c = list.insert_before(Some(d), 'c');
+    list.push_back('e');
+    assert_eq!(list.iter().collect::<String>(), "abcde");
+    assert_eq!(list.iter().rev().collect::<String>(), "edcba");
+    assert_eq!(list.next_id(a), Some(b));
+    assert_eq!(list.prev_id(a), None);
+    assert_eq!(list.nth_id(2), Some(c));
+
+    // [b, c] を末尾に，dを先頭に付け替える
+    let e = list.back_id();
+    list.splice_after(b, c, e);
+    assert_eq!(list.iter().collect::<String>(), "adebc");
+    list.splice_after(d, d, None);
+    assert_eq!(list.iter().collect::<String>(), "daebc");
+
+    assert_eq!(list.remove(a), 'a');
+    // 削除した場所は使い回す
+    let f = list.push_back('f');
+    assert_eq!(f, a);
+    list[f] = 'F';
+    for x in list.iter_mut() {
+        *x = x.to_ascii_uppercase();
+    }
+    assert_eq!(format!("{:?}", list), "['D', 'E', 'B', 'C', 'F']");
+
+    // カーソルで母音を消し，各文字の後ろに'-'を入れる
+    let mut cursor = list.cursor_front_mut();
+    while let Some(&mut x) = cursor.current() {
+        if x == 'E' {
+            cursor.remove_current();
+        } else {
+            cursor.insert_after('-');
+            cursor.move_next();
+            cursor.move_next();
+        }
+    }
+    assert_eq!(cursor.id(), None);
+    cursor.move_prev();
+    assert_eq!(cursor.peek_prev(), Some(&mut 'F'));
+    assert_eq!(list.iter().collect::<String>(), "D-B-C-F-");
+
+    let mut other = "xyz".chars().collect::<ArenaList<_>>();
+    list.append(&mut other);
+    assert!(other.is_empty());
+    assert_eq!(list.pop_back(), Some('z'));
+    assert_eq!(list.pop_front(), Some('D'));
+    assert_eq!(list.len(), 9);
+}
+
+#[test]
+fn test_arena_list_with_ddlist() {
+    use crate::data_structure::ddlist::DLList;
+    use crate::rand::xoshiro256::Xoshiro256;
+
+    // Listとしての振る舞いをDLListと比べる
+    let mut rng = Xoshiro256::new(8192);
+    let mut arena = ArenaList::new();
+    let mut dllist = DLList::new();
+    let q = if cfg!(miri) { 200 } else { 3000 };
+    for _ in 0..q {
+        let n = dllist.size();
+        match rng.gen_usize(0, 4) {
+            0 | 1 => {
+                let i = rng.gen_usize(0, n + 1);
+                let x = rng.gen_i64(0, 100);
+                List::add(&mut arena, i, x);
+                dllist.add(i, x);
+            }
+            2 => {
+                if n > 0 {
+                    let i = rng.gen_usize(0, n);
+                    assert_eq!(List::remove(&mut arena, i), dllist.remove(i));
+                }
+            }
+            _ => {
+                if n > 0 {
+                    let i = rng.gen_usize(0, n);
+                    assert_eq!(List::get(&arena, i), dllist.get(i));
+                    let x = rng.gen_i64(0, 100);
+                    assert_eq!(List::set(&mut arena, i, x), dllist.set(i, x));
+                }
+            }
+        }
+        assert_eq!(arena.size(), dllist.size());
+    }
+    let expected = (0..dllist.size()).map(|i| dllist.get(i).unwrap()).collect::<Vec<_>>();
+    assert_eq!(arena.iter().copied().collect::<Vec<_>>(), expected);
+}
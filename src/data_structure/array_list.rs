@@ -0,0 +1,74 @@
+use cargo_snippet::snippet;
+use crate::data_structure::list::List;
+
+#[snippet("r3yohei_array_list")]
+use std::collections::VecDeque;
+
+/// 配列(リングバッファ)によるリスト
+/// i番目の参照がO(1)，i番目への挿入･削除は両端に近いほうをずらすのでO(min(i, N-i))
+#[snippet("r3yohei_array_list")]
+#[derive(Clone, Debug, Default)]
+pub struct ArrayList<T> {
+    a: VecDeque<T>,
+}
+#[snippet("r3yohei_array_list")]
+impl<T> ArrayList<T> {
+    pub fn new() -> Self {
+        Self { a: VecDeque::new() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.a.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.a.is_empty()
+    }
+
+    pub fn iter(&self) -> std::collections::vec_deque::Iter<'_, T> {
+        self.a.iter()
+    }
+}
+#[snippet("r3yohei_array_list")]
+impl<T> From<Vec<T>> for ArrayList<T> {
+    fn from(v: Vec<T>) -> Self {
+        Self { a: v.into() }
+    }
+}
+#[snippet("r3yohei_array_list")]
+#[snippet(include = "r3yohei_list")]
+impl<T: Clone> List<T> for ArrayList<T> {
+    fn size(&self) -> usize {
+        self.a.len()
+    }
+    fn get(&self, i: usize) -> Option<T> {
+        self.a.get(i).cloned()
+    }
+    fn set(&mut self, i: usize, x: T) -> Option<T> {
+        self.a.get_mut(i).map(|y| std::mem::replace(y, x))
+    }
+    fn add(&mut self, i: usize, x: T) {
+        self.a.insert(i, x);
+    }
+    fn remove(&mut self, i: usize) -> Option<T> {
+        self.a.remove(i)
+    }
+    fn visit(&self, f: &mut dyn FnMut(&T) -> bool) {
+        for x in self.a.iter() {
+            if !f(x) {
+                break;
+            }
+        }
+    }
+    fn add_all(&mut self, i: usize, xs: Vec<T>) {
+        let mut tail = self.a.split_off(i);
+        self.a.extend(xs);
+        self.a.append(&mut tail);
+    }
+    fn remove_range(&mut self, l: usize, r: usize) -> Vec<T> {
+        self.a.drain(l..r).collect()
+    }
+    fn clear(&mut self) {
+        self.a.clear();
+    }
+}
@@ -0,0 +1,241 @@
+use cargo_snippet::snippet;
+
+#[snippet("r3yohei_BoundedSortedList")]
+use std::cmp::Ordering;
+#[snippet("r3yohei_BoundedSortedList")]
+use std::collections::BinaryHeap;
+
+/// 全順序で比べられるキー
+/// 浮動小数点数はtotal_cmpで比べるので，NaNでもpanicしない(NaNは最大，負のNaNは最小)
+#[snippet("r3yohei_BoundedSortedList")]
+pub trait TotalOrd {
+    fn total_order(&self, other: &Self) -> Ordering;
+}
+#[snippet("r3yohei_BoundedSortedList")]
+macro_rules! impl_total_ord {
+    ($($t:ty),*) => {
+        $(impl TotalOrd for $t {
+            fn total_order(&self, other: &Self) -> Ordering {
+                self.cmp(other)
+            }
+        })*
+    };
+}
+#[snippet("r3yohei_BoundedSortedList")]
+impl_total_ord!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, bool, char);
+#[snippet("r3yohei_BoundedSortedList")]
+impl TotalOrd for f64 {
+    fn total_order(&self, other: &Self) -> Ordering {
+        self.total_cmp(other)
+    }
+}
+#[snippet("r3yohei_BoundedSortedList")]
+impl TotalOrd for f32 {
+    fn total_order(&self, other: &Self) -> Ordering {
+        self.total_cmp(other)
+    }
+}
+#[snippet("r3yohei_BoundedSortedList")]
+impl<A: TotalOrd, B: TotalOrd> TotalOrd for (A, B) {
+    fn total_order(&self, other: &Self) -> Ordering {
+        self.0.total_order(&other.0).then_with(|| self.1.total_order(&other.1))
+    }
+}
+
+/// キーが同じなら先に入れたもの(seqが小さいもの)を良いとする
+/// 良いものほど小さくなるように並べる
+#[snippet("r3yohei_BoundedSortedList")]
+#[derive(Clone, Debug)]
+struct Entry<K, V> {
+    k: K,
+    seq: u64,
+    max: bool,
+    v: V,
+}
+#[snippet("r3yohei_BoundedSortedList")]
+impl<K: TotalOrd, V> Ord for Entry<K, V> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let ord = self.k.total_order(&other.k);
+        let ord = if self.max { ord.reverse() } else { ord };
+        ord.then(self.seq.cmp(&other.seq))
+    }
+}
+#[snippet("r3yohei_BoundedSortedList")]
+impl<K: TotalOrd, V> PartialOrd for Entry<K, V> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+#[snippet("r3yohei_BoundedSortedList")]
+impl<K: TotalOrd, V> PartialEq for Entry<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+#[snippet("r3yohei_BoundedSortedList")]
+impl<K: TotalOrd, V> Eq for Entry<K, V> {}
+
+/// Kが小さい(new_maxなら大きい)トップn個を保持する
+/// キーが同じなら先に入れたものを残すので，入れる順が同じなら結果も同じになる
+#[snippet("r3yohei_BoundedSortedList")]
+#[derive(Clone, Debug)]
+pub struct BoundedSortedList<K: TotalOrd, V> {
+    // 先頭が保持しているうちで最も悪いもの
+    que: BinaryHeap<Entry<K, V>>,
+    size: usize,
+    max: bool,
+    seq: u64,
+}
+#[snippet("r3yohei_BoundedSortedList")]
+impl<K: TotalOrd, V> BoundedSortedList<K, V> {
+    /// Kが小さいトップsize個
+    pub fn new(size: usize) -> Self {
+        Self {
+            que: BinaryHeap::with_capacity(size),
+            size,
+            max: false,
+            seq: 0,
+        }
+    }
+
+    /// Kが大きいトップsize個
+    pub fn new_max(size: usize) -> Self {
+        Self { max: true, ..Self::new(size) }
+    }
+
+    /// kを入れたら残るか．評価の重いvを作る前に確かめる
+    pub fn can_insert(&self, k: &K) -> bool {
+        if self.que.len() < self.size {
+            return true;
+        }
+        match self.que.peek() {
+            Some(top) => {
+                let ord = k.total_order(&top.k);
+                (if self.max { ord.reverse() } else { ord }) == Ordering::Less
+            }
+            None => false,
+        }
+    }
+
+    /// (k, v)を入れて，残ったらtrueを返す
+    pub fn insert(&mut self, k: K, v: V) -> bool {
+        if !self.can_insert(&k) {
+            return false;
+        }
+        let entry = Entry { k, seq: self.seq, max: self.max, v };
+        self.seq += 1;
+        if self.que.len() < self.size {
+            self.que.push(entry);
+        } else if let Some(mut top) = self.que.peek_mut() {
+            *top = entry;
+        }
+        true
+    }
+
+    pub fn len(&self) -> usize {
+        self.que.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.que.is_empty()
+    }
+
+    /// 保持しているうちで最も悪いもの
+    pub fn worst(&self) -> Option<(&K, &V)> {
+        self.que.peek().map(|e| (&e.k, &e.v))
+    }
+
+    /// 良い順に並べたもの
+    pub fn list(&self) -> Vec<(K, V)>
+    where
+        K: Clone,
+        V: Clone,
+    {
+        self.clone().into_sorted_vec()
+    }
+
+    /// 良い順に並べたもの．複製しない
+    pub fn into_sorted_vec(self) -> Vec<(K, V)> {
+        self.que.into_sorted_vec().into_iter().map(|e| (e.k, e.v)).collect()
+    }
+}
+
+/// 候補をまとめて受け取り，Kが小さい(maxなら大きい)トップsize個を良い順に返す
+/// select_nth_unstableで選ぶのでO(N + size log size)．キーが同じなら前にあるものを残す
+#[snippet("r3yohei_BoundedSortedList")]
+pub fn top_k<K: TotalOrd, V>(cands: Vec<(K, V)>, size: usize, max: bool) -> Vec<(K, V)> {
+    let mut entries = cands
+        .into_iter()
+        .enumerate()
+        .map(|(i, (k, v))| Entry { k, seq: i as u64, max, v })
+        .collect::<Vec<_>>();
+    if size == 0 {
+        return vec![];
+    }
+    if entries.len() > size {
+        entries.select_nth_unstable(size - 1);
+        entries.truncate(size);
+    }
+    entries.sort_unstable();
+    entries.into_iter().map(|e| (e.k, e.v)).collect()
+}
+
+#[test]
+fn test_bounded_sorted_list() {
+    let mut list = BoundedSortedList::new(3);
+    for (k, v) in [(5, 'a'), (3, 'b'), (5, 'c'), (1, 'd'), (3, 'e'), (4, 'f')] {
+        list.insert(k, v);
+    }
+    // 同じキーなら先に入れたものが残る
+    assert_eq!(list.list(), vec![(1, 'd'), (3, 'b'), (3, 'e')]);
+    assert!(!list.can_insert(&3));
+    assert!(list.can_insert(&2));
+    assert_eq!(list.worst(), Some((&3, &'e')));
+
+    let mut list = BoundedSortedList::new_max(2);
+    for (k, v) in [(5, 'a'), (3, 'b'), (5, 'c'), (7, 'd')] {
+        list.insert(k, v);
+    }
+    assert_eq!(list.into_sorted_vec(), vec![(7, 'd'), (5, 'a')]);
+
+    // NaNがあってもpanicしない
+    let mut list = BoundedSortedList::new(2);
+    for (k, v) in [(0.5, 0), (f64::NAN, 1), (-1.0, 2), (0.5, 3)] {
+        list.insert(k, v);
+    }
+    assert_eq!(list.into_sorted_vec(), vec![(-1.0, 2), (0.5, 0)]);
+    let mut list = BoundedSortedList::new_max(1);
+    list.insert(1.0, 0);
+    list.insert(f64::NAN, 1);
+    assert!(list.into_sorted_vec()[0].0.is_nan());
+
+    // 複製できない値も入れられる
+    let mut list = BoundedSortedList::new(1);
+    list.insert((2, 1), vec![String::from("x")]);
+    list.insert((2, 0), vec![String::from("y")]);
+    assert_eq!(list.into_sorted_vec(), vec![((2, 0), vec![String::from("y")])]);
+    assert!(!BoundedSortedList::<i64, ()>::new(0).insert(0, ()));
+}
+
+#[test]
+fn test_top_k() {
+    use crate::rand::xoshiro256::Xoshiro256;
+
+    let mut rng = Xoshiro256::new(8192);
+    for _ in 0..100 {
+        let n = rng.gen_usize(0, 200);
+        let size = rng.gen_usize(0, 50);
+        let max = rng.gen_bool(0.5);
+        let cands = (0..n).map(|i| (rng.gen_i64(0, 20), i)).collect::<Vec<_>>();
+        let mut list = if max { BoundedSortedList::new_max(size) } else { BoundedSortedList::new(size) };
+        for &(k, v) in &cands {
+            list.insert(k, v);
+        }
+        let mut expected = cands.clone();
+        // 安定ソートなので，キーが同じなら前にあるものが先
+        expected.sort_by_key(|&(k, _)| if max { -k } else { k });
+        expected.truncate(size);
+        assert_eq!(list.into_sorted_vec(), expected);
+        assert_eq!(top_k(cands, size, max), expected);
+    }
+}
@@ -1,4 +1,5 @@
 use cargo_snippet::snippet;
+use crate::data_structure::list::List;
 
 #[snippet("r3yohei_ddlist")]
 use std::cell::RefCell;
@@ -10,15 +11,6 @@ type Link<T> = Option<Rc<RefCell<Node<T>>>>;
 #[snippet("r3yohei_ddlist")]
 type Wink<T> = Option<Weak<RefCell<Node<T>>>>;
 
-#[snippet("r3yohei_ddlist")]
-pub trait List<T: Clone> {
-    fn size(&self) -> usize;
-    fn get(&self, i: usize) -> Option<T>;
-    fn set(&mut self, i: usize, x: T) -> Option<T>;
-    fn add(&mut self, i: usize, x: T);
-    fn remove(&mut self, i: usize) -> Option<T>;
-}
-
 #[snippet("r3yohei_ddlist")]
 #[derive(Clone, Debug, Default)]
 pub struct DLList<T: Clone + Default> {
@@ -111,6 +103,7 @@ impl<T: Default + Clone> DLList<T> {
 }
 
 #[snippet("r3yohei_ddlist")]
+#[snippet(include = "r3yohei_list")]
 impl<T: Clone + Default> List<T> for DLList<T> {
     fn size(&self) -> usize {
         self.n
@@ -148,6 +141,16 @@ impl<T: Clone + Default> List<T> for DLList<T> {
             None => None,
         }
     }
+    fn visit(&self, f: &mut dyn FnMut(&T) -> bool) {
+        let mut p = self.head.as_ref().and_then(|d| d.borrow().next.clone());
+        for _ in 0..self.n {
+            let node = p.unwrap();
+            if !f(&node.borrow().x) {
+                return;
+            }
+            p = node.borrow().next.clone();
+        }
+    }
 }
 
 #[test]
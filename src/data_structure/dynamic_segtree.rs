@@ -0,0 +1,149 @@
+use cargo_snippet::snippet;
+use crate::data_structure::segtree::Monoid;
+
+/// 動的セグメント木の節点．子がなければ!0
+#[snippet("r3yohei_DynamicSegTree")]
+#[derive(Clone, Debug)]
+struct DynamicNode<S> {
+    val: S,
+    left: usize,
+    right: usize,
+}
+
+/// 動的セグメント木
+/// 添字が[lo, hi) (幅1e18程度まで)の範囲で，触った場所の節点だけを作る
+/// 一点更新と区間の積がO(log(hi - lo))，節点数はO(Q log(hi - lo))
+#[snippet("r3yohei_DynamicSegTree")]
+#[snippet(include = "r3yohei_Monoid")]
+pub struct DynamicSegTree<M: Monoid> {
+    lo: i64,
+    hi: i64,
+    nodes: Vec<DynamicNode<M::S>>,
+}
+#[snippet("r3yohei_DynamicSegTree")]
+impl<M: Monoid> DynamicSegTree<M> {
+    pub fn new(lo: i64, hi: i64) -> Self {
+        assert!(lo < hi);
+        Self {
+            lo,
+            hi,
+            nodes: vec![DynamicNode { val: M::identity(), left: !0, right: !0 }],
+        }
+    }
+
+    fn new_node(&mut self) -> usize {
+        self.nodes.push(DynamicNode { val: M::identity(), left: !0, right: !0 });
+        self.nodes.len() - 1
+    }
+
+    fn val(&self, v: usize) -> M::S {
+        if v == !0 { M::identity() } else { self.nodes[v].val.clone() }
+    }
+
+    pub fn set(&mut self, p: i64, x: M::S) {
+        assert!(self.lo <= p && p < self.hi);
+        // 根から葉まで下りながら節点を作り，帰りに値を更新する
+        let mut path = vec![];
+        let (mut v, mut l, mut r) = (0, self.lo, self.hi);
+        while r - l > 1 {
+            path.push(v);
+            let m = l + (r - l) / 2;
+            if p < m {
+                if self.nodes[v].left == !0 {
+                    let c = self.new_node();
+                    self.nodes[v].left = c;
+                }
+                v = self.nodes[v].left;
+                r = m;
+            } else {
+                if self.nodes[v].right == !0 {
+                    let c = self.new_node();
+                    self.nodes[v].right = c;
+                }
+                v = self.nodes[v].right;
+                l = m;
+            }
+        }
+        self.nodes[v].val = x;
+        for &v in path.iter().rev() {
+            let (left, right) = (self.nodes[v].left, self.nodes[v].right);
+            self.nodes[v].val = M::binary_operation(&self.val(left), &self.val(right));
+        }
+    }
+
+    pub fn get(&self, p: i64) -> M::S {
+        assert!(self.lo <= p && p < self.hi);
+        let (mut v, mut l, mut r) = (0, self.lo, self.hi);
+        while r - l > 1 {
+            let m = l + (r - l) / 2;
+            if p < m {
+                v = self.nodes[v].left;
+                r = m;
+            } else {
+                v = self.nodes[v].right;
+                l = m;
+            }
+            if v == !0 {
+                return M::identity();
+            }
+        }
+        self.nodes[v].val.clone()
+    }
+
+    /// [l, r) の積
+    pub fn prod(&self, l: i64, r: i64) -> M::S {
+        assert!(self.lo <= l && l <= r && r <= self.hi);
+        self.prod_rec(0, self.lo, self.hi, l, r)
+    }
+
+    fn prod_rec(&self, v: usize, vl: i64, vr: i64, l: i64, r: i64) -> M::S {
+        if v == !0 || r <= vl || vr <= l {
+            return M::identity();
+        }
+        if l <= vl && vr <= r {
+            return self.nodes[v].val.clone();
+        }
+        let m = vl + (vr - vl) / 2;
+        M::binary_operation(
+            &self.prod_rec(self.nodes[v].left, vl, m, l, r),
+            &self.prod_rec(self.nodes[v].right, m, vr, l, r),
+        )
+    }
+
+    pub fn all_prod(&self) -> M::S {
+        self.nodes[0].val.clone()
+    }
+}
+
+#[test]
+fn test_dynamic_segtree() {
+    use crate::data_structure::segtree::{Additive, Min};
+    use crate::rand::xoshiro256::Xoshiro256;
+    use std::collections::BTreeMap;
+
+    let mut rng = Xoshiro256::new(8192);
+    let (lo, hi) = (-1_000_000_000_000_000_000, 1_000_000_000_000_000_000);
+    let mut sum = DynamicSegTree::<Additive<i64>>::new(lo, hi);
+    let mut min = DynamicSegTree::<Min<i64>>::new(lo, hi);
+    let mut naive = BTreeMap::new();
+    // 近い位置が選ばれやすいように，少ない候補から選ぶ
+    let points = (0..30).map(|_| rng.gen_i64(lo, hi)).chain([lo, hi - 1, 0]).collect::<Vec<_>>();
+    for _ in 0..500 {
+        let p = points[rng.gen_usize(0, points.len())];
+        let x = rng.gen_i64(-100, 100);
+        sum.set(p, x);
+        min.set(p, x);
+        naive.insert(p, x);
+        assert_eq!(sum.get(p), x);
+
+        let mut l = points[rng.gen_usize(0, points.len())];
+        let mut r = points[rng.gen_usize(0, points.len())];
+        if l > r {
+            std::mem::swap(&mut l, &mut r);
+        }
+        assert_eq!(sum.prod(l, r), naive.range(l..r).map(|(_, &x)| x).sum::<i64>());
+        assert_eq!(min.prod(l, r), naive.range(l..r).map(|(_, &x)| x).min().unwrap_or(i64::MAX));
+    }
+    assert_eq!(sum.all_prod(), naive.values().sum::<i64>());
+    assert_eq!(sum.get(1), if naive.contains_key(&1) { naive[&1] } else { 0 });
+}
@@ -0,0 +1,355 @@
+use cargo_snippet::snippet;
+
+#[snippet("r3yohei_FenwickTree")]
+use std::ops::{Add, Sub};
+
+/// フェニック木(BIT)
+/// 一点加算と接頭辞の和がO(logN)．Tは加算と減算ができる群(i64やModIntなど)
+#[snippet("r3yohei_FenwickTree")]
+#[derive(Clone, Debug)]
+pub struct FenwickTree<T> {
+    n: usize,
+    // 1-indexed
+    data: Vec<T>,
+}
+#[snippet("r3yohei_FenwickTree")]
+impl<T: Copy + Default + Add<Output = T> + Sub<Output = T>> FenwickTree<T> {
+    pub fn new(n: usize) -> Self {
+        Self { n, data: vec![T::default(); n + 1] }
+    }
+
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    /// i番目にxを足す
+    pub fn add(&mut self, i: usize, x: T) {
+        assert!(i < self.n);
+        let mut i = i + 1;
+        while i <= self.n {
+            self.data[i] = self.data[i] + x;
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    /// [0, r) の和
+    pub fn sum(&self, r: usize) -> T {
+        assert!(r <= self.n);
+        let mut s = T::default();
+        let mut r = r;
+        while r > 0 {
+            s = s + self.data[r];
+            r -= r & r.wrapping_neg();
+        }
+        s
+    }
+
+    /// [l, r) の和
+    pub fn range_sum(&self, l: usize, r: usize) -> T {
+        assert!(l <= r);
+        self.sum(r) - self.sum(l)
+    }
+
+    /// sum(i + 1) >= wとなる最小のi．なければn
+    /// 各要素が非負のときに使う．個数を持たせれば，(0-indexedで)k番目に小さい値はlower_bound(k + 1)
+    pub fn lower_bound(&self, mut w: T) -> usize
+    where
+        T: PartialOrd,
+    {
+        let mut i = 0;
+        let mut step = 1;
+        while step * 2 <= self.n {
+            step *= 2;
+        }
+        while step > 0 {
+            if i + step <= self.n && self.data[i + step] < w {
+                w = w - self.data[i + step];
+                i += step;
+            }
+            step >>= 1;
+        }
+        i
+    }
+}
+#[snippet("r3yohei_FenwickTree")]
+impl<T: Copy + Default + Add<Output = T> + Sub<Output = T>> From<Vec<T>> for FenwickTree<T> {
+    /// O(N)で作る
+    fn from(v: Vec<T>) -> Self {
+        let n = v.len();
+        let mut data = vec![T::default(); n + 1];
+        data[1..].copy_from_slice(&v);
+        for i in 1..=n {
+            let j = i + (i & i.wrapping_neg());
+            if j <= n {
+                data[j] = data[j] + data[i];
+            }
+        }
+        Self { n, data }
+    }
+}
+
+/// 区間加算･区間和のフェニック木
+/// sum(r) = b0.sum(r) + b1.sum(r) * r となるように2本のBITを持つ
+#[snippet("r3yohei_RangeAddFenwickTree")]
+#[snippet(include = "r3yohei_FenwickTree")]
+#[derive(Clone, Debug)]
+pub struct RangeAddFenwickTree {
+    b0: FenwickTree<i64>,
+    b1: FenwickTree<i64>,
+}
+#[snippet("r3yohei_RangeAddFenwickTree")]
+impl RangeAddFenwickTree {
+    pub fn new(n: usize) -> Self {
+        // 区間の右端nにも足すので，1つ多く持つ
+        Self { b0: FenwickTree::new(n + 1), b1: FenwickTree::new(n + 1) }
+    }
+
+    /// [l, r) にxを足す
+    pub fn add(&mut self, l: usize, r: usize, x: i64) {
+        assert!(l <= r && r < self.b0.len());
+        self.b0.add(l, -x * l as i64);
+        self.b1.add(l, x);
+        self.b0.add(r, x * r as i64);
+        self.b1.add(r, -x);
+    }
+
+    /// [0, r) の和
+    pub fn sum(&self, r: usize) -> i64 {
+        self.b0.sum(r) + self.b1.sum(r) * r as i64
+    }
+
+    /// [l, r) の和
+    pub fn range_sum(&self, l: usize, r: usize) -> i64 {
+        self.sum(r) - self.sum(l)
+    }
+}
+
+/// 2次元フェニック木
+/// 一点加算と長方形の和がO(logH logW)
+#[snippet("r3yohei_FenwickTree2D")]
+#[snippet(include = "r3yohei_FenwickTree")]
+#[derive(Clone, Debug)]
+pub struct FenwickTree2D<T> {
+    h: usize,
+    w: usize,
+    data: Vec<Vec<T>>,
+}
+#[snippet("r3yohei_FenwickTree2D")]
+impl<T: Copy + Default + Add<Output = T> + Sub<Output = T>> FenwickTree2D<T> {
+    pub fn new(h: usize, w: usize) -> Self {
+        Self { h, w, data: vec![vec![T::default(); w + 1]; h + 1] }
+    }
+
+    /// (i, j)にxを足す
+    pub fn add(&mut self, i: usize, j: usize, x: T) {
+        assert!(i < self.h && j < self.w);
+        let mut i = i + 1;
+        while i <= self.h {
+            let mut j = j + 1;
+            while j <= self.w {
+                self.data[i][j] = self.data[i][j] + x;
+                j += j & j.wrapping_neg();
+            }
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    /// [0, i) × [0, j) の和
+    pub fn sum(&self, i: usize, j: usize) -> T {
+        assert!(i <= self.h && j <= self.w);
+        let mut s = T::default();
+        let mut i = i;
+        while i > 0 {
+            let mut j = j;
+            while j > 0 {
+                s = s + self.data[i][j];
+                j -= j & j.wrapping_neg();
+            }
+            i -= i & i.wrapping_neg();
+        }
+        s
+    }
+
+    /// [i1, i2) × [j1, j2) の和
+    pub fn rect_sum(&self, i1: usize, j1: usize, i2: usize, j2: usize) -> T {
+        assert!(i1 <= i2 && j1 <= j2);
+        self.sum(i2, j2) - self.sum(i1, j2) - self.sum(i2, j1) + self.sum(i1, j1)
+    }
+}
+
+/// 接頭辞の最大値を持つフェニック木
+/// 値は大きくする更新(chmax)しかできない．DPの高速化に使う
+#[snippet("r3yohei_FenwickTreeMax")]
+#[derive(Clone, Debug)]
+pub struct FenwickTreeMax<T> {
+    n: usize,
+    init: T,
+    data: Vec<T>,
+}
+#[snippet("r3yohei_FenwickTreeMax")]
+impl<T: Copy + Ord> FenwickTreeMax<T> {
+    /// 全ての値がinitで初期化される
+    pub fn new(n: usize, init: T) -> Self {
+        Self { n, init, data: vec![init; n + 1] }
+    }
+
+    /// i番目をmax(i番目, x)にする
+    pub fn chmax(&mut self, i: usize, x: T) {
+        assert!(i < self.n);
+        let mut i = i + 1;
+        while i <= self.n {
+            if self.data[i] < x {
+                self.data[i] = x;
+            }
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    /// [0, r) の最大値．r = 0ならinit
+    pub fn max(&self, r: usize) -> T {
+        assert!(r <= self.n);
+        let mut m = self.init;
+        let mut r = r;
+        while r > 0 {
+            m = m.max(self.data[r]);
+            r -= r & r.wrapping_neg();
+        }
+        m
+    }
+}
+
+/// 転倒数(i < jかつa[i] > a[j]となる組の数) O(NlogN)
+#[snippet("r3yohei_inversion_count")]
+#[snippet(include = "r3yohei_FenwickTree")]
+pub fn inversion_count<T: Ord>(a: &[T]) -> usize {
+    // 座標圧縮
+    let mut idx = (0..a.len()).collect::<Vec<_>>();
+    idx.sort_by(|&i, &j| a[i].cmp(&a[j]));
+    let mut rank = vec![0; a.len()];
+    let mut r = 0;
+    for k in 0..idx.len() {
+        if k > 0 && a[idx[k - 1]] < a[idx[k]] {
+            r += 1;
+        }
+        rank[idx[k]] = r;
+    }
+    let mut bit = FenwickTree::<usize>::new(a.len());
+    let mut cnt = 0;
+    for (i, &r) in rank.iter().enumerate() {
+        // 前にあってrより大きいものの数
+        cnt += i - bit.sum(r + 1);
+        bit.add(r, 1);
+    }
+    cnt
+}
+
+#[test]
+fn test_fenwick_tree() {
+    use crate::rand::xoshiro256::Xoshiro256;
+
+    let mut rng = Xoshiro256::new(8192);
+    for n in [0, 1, 2, 7, 16, 33] {
+        let mut a = (0..n).map(|_| rng.gen_i64(0, 5)).collect::<Vec<_>>();
+        let mut bit = FenwickTree::from(a.clone());
+        assert_eq!(bit.len(), n);
+        for _ in 0..200 {
+            if n > 0 {
+                let i = rng.gen_usize(0, n);
+                let x = rng.gen_i64(0, 5);
+                a[i] += x;
+                bit.add(i, x);
+            }
+            let l = rng.gen_usize(0, n + 1);
+            let r = rng.gen_usize(l, n + 1);
+            assert_eq!(bit.range_sum(l, r), a[l..r].iter().sum::<i64>());
+            let w = rng.gen_i64(0, a.iter().sum::<i64>() + 2);
+            let expected = (0..n).find(|&i| a[..=i].iter().sum::<i64>() >= w).unwrap_or(n);
+            assert_eq!(bit.lower_bound(w), expected);
+        }
+    }
+
+    // 多重集合のk番目
+    let mut cnt = FenwickTree::<usize>::new(10);
+    for x in [3, 1, 4, 1, 5, 9] {
+        cnt.add(x, 1);
+    }
+    let kth = (0..6).map(|k| cnt.lower_bound(k + 1)).collect::<Vec<_>>();
+    assert_eq!(kth, vec![1, 1, 3, 4, 5, 9]);
+
+    let mut bit = FenwickTreeMax::new(5, 0);
+    bit.chmax(2, 7);
+    bit.chmax(0, 3);
+    assert_eq!((bit.max(0), bit.max(2), bit.max(3), bit.max(5)), (0, 3, 7, 7));
+}
+
+#[test]
+fn test_range_add_fenwick_tree() {
+    use crate::rand::xoshiro256::Xoshiro256;
+
+    let mut rng = Xoshiro256::new(8192);
+    let n = 20;
+    let mut a = vec![0; n];
+    let mut bit = RangeAddFenwickTree::new(n);
+    for _ in 0..500 {
+        let l = rng.gen_usize(0, n + 1);
+        let r = rng.gen_usize(l, n + 1);
+        if rng.gen_bool(0.5) {
+            let x = rng.gen_i64(-10, 10);
+            bit.add(l, r, x);
+            for v in a[l..r].iter_mut() {
+                *v += x;
+            }
+        } else {
+            assert_eq!(bit.range_sum(l, r), a[l..r].iter().sum::<i64>());
+        }
+    }
+}
+
+#[test]
+fn test_fenwick_tree_2d() {
+    use crate::rand::xoshiro256::Xoshiro256;
+
+    let mut rng = Xoshiro256::new(8192);
+    let (h, w) = (6, 9);
+    let mut a = vec![vec![0; w]; h];
+    let mut bit = FenwickTree2D::new(h, w);
+    for _ in 0..300 {
+        let (i, j) = (rng.gen_usize(0, h), rng.gen_usize(0, w));
+        let x = rng.gen_i64(-5, 5);
+        a[i][j] += x;
+        bit.add(i, j, x);
+        let i1 = rng.gen_usize(0, h + 1);
+        let i2 = rng.gen_usize(i1, h + 1);
+        let j1 = rng.gen_usize(0, w + 1);
+        let j2 = rng.gen_usize(j1, w + 1);
+        let expected = a[i1..i2].iter().map(|row| row[j1..j2].iter().sum::<i64>()).sum::<i64>();
+        assert_eq!(bit.rect_sum(i1, j1, i2, j2), expected);
+    }
+}
+
+#[test]
+fn test_inversion_count() {
+    use crate::rand::xoshiro256::Xoshiro256;
+
+    assert_eq!(inversion_count(&[3, 1, 2]), 2);
+    assert_eq!(inversion_count::<i64>(&[]), 0);
+    assert_eq!(inversion_count(&["b", "a", "b", "a"]), 3);
+    let mut rng = Xoshiro256::new(8192);
+    for _ in 0..50 {
+        let n = rng.gen_usize(0, 50);
+        let a = (0..n).map(|_| rng.gen_i64(0, 10)).collect::<Vec<_>>();
+        let mut expected = 0;
+        for i in 0..n {
+            for j in i + 1..n {
+                if a[i] > a[j] {
+                    expected += 1;
+                }
+            }
+        }
+        assert_eq!(inversion_count(&a), expected);
+    }
+}
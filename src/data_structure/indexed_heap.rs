@@ -0,0 +1,193 @@
+use cargo_snippet::snippet;
+
+/// 添字付きの優先度付きキュー(二分ヒープ)
+/// 添字0..nにキーを1つずつ持たせ，キーが最小のものから取り出す
+/// 同じ添字を何度も入れないので，ダイクストラ法などで古い値を読み飛ばさなくてよい
+/// push, pop, decrease_key, removeがO(logN)
+#[snippet("r3yohei_IndexedHeap")]
+#[derive(Clone, Debug)]
+pub struct IndexedHeap<K> {
+    // ヒープに並べた添字
+    heap: Vec<usize>,
+    // 添字がheapの何番目にあるか．入っていなければ!0
+    pos: Vec<usize>,
+    key: Vec<Option<K>>,
+}
+#[snippet("r3yohei_IndexedHeap")]
+impl<K: Ord> IndexedHeap<K> {
+    /// 添字が0..nのヒープ
+    pub fn new(n: usize) -> Self {
+        Self {
+            heap: Vec::with_capacity(n),
+            pos: vec![!0; n],
+            key: (0..n).map(|_| None).collect(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    pub fn contains(&self, id: usize) -> bool {
+        self.pos[id] != !0
+    }
+
+    /// idのキー
+    pub fn get(&self, id: usize) -> Option<&K> {
+        self.key[id].as_ref()
+    }
+
+    fn less(&self, i: usize, j: usize) -> bool {
+        self.key[self.heap[i]] < self.key[self.heap[j]]
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        self.heap.swap(i, j);
+        self.pos[self.heap[i]] = i;
+        self.pos[self.heap[j]] = j;
+    }
+
+    fn sift_up(&mut self, mut i: usize) {
+        while i > 0 {
+            let p = (i - 1) / 2;
+            if !self.less(i, p) {
+                break;
+            }
+            self.swap(i, p);
+            i = p;
+        }
+    }
+
+    fn sift_down(&mut self, mut i: usize) {
+        loop {
+            let (l, r) = (2 * i + 1, 2 * i + 2);
+            let mut m = i;
+            if l < self.heap.len() && self.less(l, m) {
+                m = l;
+            }
+            if r < self.heap.len() && self.less(r, m) {
+                m = r;
+            }
+            if m == i {
+                break;
+            }
+            self.swap(i, m);
+            i = m;
+        }
+    }
+
+    /// idのキーをkeyにする．入っていなければ入れる
+    pub fn push(&mut self, id: usize, key: K) {
+        if self.contains(id) {
+            self.key[id] = Some(key);
+            let i = self.pos[id];
+            self.sift_up(i);
+            self.sift_down(self.pos[id]);
+        } else {
+            self.key[id] = Some(key);
+            self.pos[id] = self.heap.len();
+            self.heap.push(id);
+            self.sift_up(self.heap.len() - 1);
+        }
+    }
+
+    /// idのキーがkeyより大きい(か入っていない)ならkeyにして，trueを返す
+    pub fn decrease_key(&mut self, id: usize, key: K) -> bool {
+        if self.key[id].as_ref().map_or(false, |k| *k <= key) {
+            return false;
+        }
+        self.push(id, key);
+        true
+    }
+
+    /// キーが最小のもの
+    pub fn peek(&self) -> Option<(usize, &K)> {
+        self.heap.first().map(|&id| (id, self.key[id].as_ref().unwrap()))
+    }
+
+    /// キーが最小のものを取り出す
+    pub fn pop(&mut self) -> Option<(usize, K)> {
+        let id = *self.heap.first()?;
+        self.remove(id).map(|key| (id, key))
+    }
+
+    /// idを取り除き，そのキーを返す
+    pub fn remove(&mut self, id: usize) -> Option<K> {
+        if !self.contains(id) {
+            return None;
+        }
+        let i = self.pos[id];
+        let last = self.heap.len() - 1;
+        self.swap(i, last);
+        self.heap.pop();
+        self.pos[id] = !0;
+        if i < self.heap.len() {
+            // 末尾から移したものを正しい位置に動かす
+            let moved = self.heap[i];
+            self.sift_up(i);
+            self.sift_down(self.pos[moved]);
+        }
+        self.key[id].take()
+    }
+}
+
+#[test]
+fn test_indexed_heap() {
+    use crate::rand::xoshiro256::Xoshiro256;
+    use std::collections::BTreeSet;
+
+    let mut rng = Xoshiro256::new(8192);
+    let n = 50;
+    let mut heap = IndexedHeap::new(n);
+    // (キー, 添字)の集合と比べる
+    let mut naive = BTreeSet::new();
+    let mut key = vec![None; n];
+    for _ in 0..5000 {
+        let id = rng.gen_usize(0, n);
+        let k = rng.gen_i64(0, 100);
+        match rng.gen_usize(0, 4) {
+            0 => {
+                heap.push(id, k);
+                if let Some(old) = key[id] {
+                    naive.remove(&(old, id));
+                }
+                naive.insert((k, id));
+                key[id] = Some(k);
+            }
+            1 => {
+                let changed = key[id].map_or(true, |old| k < old);
+                assert_eq!(heap.decrease_key(id, k), changed);
+                if changed {
+                    if let Some(old) = key[id] {
+                        naive.remove(&(old, id));
+                    }
+                    naive.insert((k, id));
+                    key[id] = Some(k);
+                }
+            }
+            2 => {
+                assert_eq!(heap.remove(id), key[id]);
+                if let Some(old) = key[id].take() {
+                    naive.remove(&(old, id));
+                }
+            }
+            _ => {
+                // キーが同じなら添字はどれでもよいので，キーだけ比べる
+                let popped = heap.pop();
+                let expected = naive.iter().next().copied();
+                assert_eq!(popped.map(|(_, k)| k), expected.map(|(k, _)| k));
+                if let Some((id, k)) = popped {
+                    assert!(naive.remove(&(k, id)));
+                    key[id] = None;
+                }
+            }
+        }
+        assert_eq!(heap.len(), naive.len());
+        assert_eq!(heap.peek().map(|(_, &k)| k), naive.iter().next().map(|&(k, _)| k));
+        assert!(heap.contains(id) == key[id].is_some());
+    }
+}
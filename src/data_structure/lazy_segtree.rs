@@ -0,0 +1,373 @@
+use cargo_snippet::snippet;
+use crate::data_structure::segtree::{Min, Monoid};
+use crate::util::modint::ModInt;
+
+/// 作用素付きモノイド
+/// Mの値に作用させる写像Fと，その合成composition(f, g) = f∘g (gの後にf) を与える
+#[snippet("r3yohei_MapMonoid")]
+#[snippet(include = "r3yohei_Monoid")]
+pub trait MapMonoid {
+    type M: Monoid;
+    type F: Clone;
+    fn identity_map() -> Self::F;
+    fn mapping(f: &Self::F, x: &<Self::M as Monoid>::S) -> <Self::M as Monoid>::S;
+    fn composition(f: &Self::F, g: &Self::F) -> Self::F;
+}
+
+/// 区間の和と長さ．区間に一次関数を作用させるときに使う
+#[snippet("r3yohei_RangeAffineRangeSum")]
+#[snippet(include = "r3yohei_MapMonoid")]
+#[snippet(include = "r3yohei_ModInt")]
+pub struct SumWithLen;
+#[snippet("r3yohei_RangeAffineRangeSum")]
+impl Monoid for SumWithLen {
+    type S = (ModInt, usize);
+    fn identity() -> Self::S {
+        (ModInt::zero(), 0)
+    }
+    fn binary_operation(a: &Self::S, b: &Self::S) -> Self::S {
+        (a.0 + b.0, a.1 + b.1)
+    }
+}
+
+/// 区間に x -> ax + b を作用させ，区間和を求める
+/// 値は(和, 長さ)で持つので，初期値は(a_i, 1)にする
+#[snippet("r3yohei_RangeAffineRangeSum")]
+pub struct RangeAffineRangeSum;
+#[snippet("r3yohei_RangeAffineRangeSum")]
+impl MapMonoid for RangeAffineRangeSum {
+    type M = SumWithLen;
+    type F = (ModInt, ModInt);
+    fn identity_map() -> Self::F {
+        (ModInt::one(), ModInt::zero())
+    }
+    fn mapping(f: &Self::F, x: &(ModInt, usize)) -> (ModInt, usize) {
+        (f.0 * x.0 + f.1 * ModInt::new(x.1), x.1)
+    }
+    fn composition(f: &Self::F, g: &Self::F) -> Self::F {
+        (f.0 * g.0, f.0 * g.1 + f.1)
+    }
+}
+
+/// 区間に加算し，区間最小値を求める
+#[snippet("r3yohei_RangeAddRangeMin")]
+#[snippet(include = "r3yohei_MapMonoid")]
+pub struct RangeAddRangeMin;
+#[snippet("r3yohei_RangeAddRangeMin")]
+impl MapMonoid for RangeAddRangeMin {
+    type M = Min<i64>;
+    type F = i64;
+    fn identity_map() -> i64 {
+        0
+    }
+    fn mapping(&f: &i64, &x: &i64) -> i64 {
+        // 単位元(空の区間)には足さない
+        if x == i64::MAX { x } else { x + f }
+    }
+    fn composition(&f: &i64, &g: &i64) -> i64 {
+        f + g
+    }
+}
+
+/// 遅延評価セグメント木
+/// 区間への作用と区間の積がO(logN)
+#[snippet("r3yohei_LazySegTree")]
+#[snippet(include = "r3yohei_MapMonoid")]
+pub struct LazySegTree<F: MapMonoid> {
+    n: usize,
+    size: usize,
+    log: usize,
+    d: Vec<<F::M as Monoid>::S>,
+    lz: Vec<F::F>,
+}
+#[snippet("r3yohei_LazySegTree")]
+impl<F: MapMonoid> LazySegTree<F> {
+    pub fn new(n: usize) -> Self {
+        Self::from(vec![F::M::identity(); n])
+    }
+
+    fn update(&mut self, k: usize) {
+        self.d[k] = F::M::binary_operation(&self.d[2 * k], &self.d[2 * k + 1]);
+    }
+
+    fn all_apply(&mut self, k: usize, f: &F::F) {
+        self.d[k] = F::mapping(f, &self.d[k]);
+        if k < self.size {
+            self.lz[k] = F::composition(f, &self.lz[k]);
+        }
+    }
+
+    // 遅延させていた作用を子に伝える
+    fn push(&mut self, k: usize) {
+        let f = std::mem::replace(&mut self.lz[k], F::identity_map());
+        self.all_apply(2 * k, &f);
+        self.all_apply(2 * k + 1, &f);
+    }
+
+    pub fn set(&mut self, mut p: usize, x: <F::M as Monoid>::S) {
+        assert!(p < self.n);
+        p += self.size;
+        for i in (1..=self.log).rev() {
+            self.push(p >> i);
+        }
+        self.d[p] = x;
+        for i in 1..=self.log {
+            self.update(p >> i);
+        }
+    }
+
+    pub fn get(&mut self, mut p: usize) -> <F::M as Monoid>::S {
+        assert!(p < self.n);
+        p += self.size;
+        for i in (1..=self.log).rev() {
+            self.push(p >> i);
+        }
+        self.d[p].clone()
+    }
+
+    /// [l, r) の積
+    pub fn prod(&mut self, mut l: usize, mut r: usize) -> <F::M as Monoid>::S {
+        assert!(l <= r && r <= self.n);
+        if l == r {
+            return F::M::identity();
+        }
+        l += self.size;
+        r += self.size;
+        for i in (1..=self.log).rev() {
+            if ((l >> i) << i) != l {
+                self.push(l >> i);
+            }
+            if ((r >> i) << i) != r {
+                self.push((r - 1) >> i);
+            }
+        }
+        let mut sml = F::M::identity();
+        let mut smr = F::M::identity();
+        while l < r {
+            if l & 1 == 1 {
+                sml = F::M::binary_operation(&sml, &self.d[l]);
+                l += 1;
+            }
+            if r & 1 == 1 {
+                r -= 1;
+                smr = F::M::binary_operation(&self.d[r], &smr);
+            }
+            l >>= 1;
+            r >>= 1;
+        }
+        F::M::binary_operation(&sml, &smr)
+    }
+
+    pub fn all_prod(&self) -> <F::M as Monoid>::S {
+        self.d[1].clone()
+    }
+
+    /// p番目にfを作用させる
+    pub fn apply(&mut self, mut p: usize, f: F::F) {
+        assert!(p < self.n);
+        p += self.size;
+        for i in (1..=self.log).rev() {
+            self.push(p >> i);
+        }
+        self.d[p] = F::mapping(&f, &self.d[p]);
+        for i in 1..=self.log {
+            self.update(p >> i);
+        }
+    }
+
+    /// [l, r) にfを作用させる
+    pub fn apply_range(&mut self, mut l: usize, mut r: usize, f: F::F) {
+        assert!(l <= r && r <= self.n);
+        if l == r {
+            return;
+        }
+        l += self.size;
+        r += self.size;
+        for i in (1..=self.log).rev() {
+            if ((l >> i) << i) != l {
+                self.push(l >> i);
+            }
+            if ((r >> i) << i) != r {
+                self.push((r - 1) >> i);
+            }
+        }
+        {
+            let (mut l, mut r) = (l, r);
+            while l < r {
+                if l & 1 == 1 {
+                    self.all_apply(l, &f);
+                    l += 1;
+                }
+                if r & 1 == 1 {
+                    r -= 1;
+                    self.all_apply(r, &f);
+                }
+                l >>= 1;
+                r >>= 1;
+            }
+        }
+        for i in 1..=self.log {
+            if ((l >> i) << i) != l {
+                self.update(l >> i);
+            }
+            if ((r >> i) << i) != r {
+                self.update((r - 1) >> i);
+            }
+        }
+    }
+
+    /// g(prod(l, r))がtrueになる最大のr．gは単調で，g(単位元)はtrueであること
+    pub fn max_right(&mut self, mut l: usize, g: impl Fn(&<F::M as Monoid>::S) -> bool) -> usize {
+        assert!(l <= self.n);
+        assert!(g(&F::M::identity()));
+        if l == self.n {
+            return self.n;
+        }
+        l += self.size;
+        for i in (1..=self.log).rev() {
+            self.push(l >> i);
+        }
+        let mut sm = F::M::identity();
+        loop {
+            while l & 1 == 0 {
+                l >>= 1;
+            }
+            if !g(&F::M::binary_operation(&sm, &self.d[l])) {
+                while l < self.size {
+                    self.push(l);
+                    l *= 2;
+                    let res = F::M::binary_operation(&sm, &self.d[l]);
+                    if g(&res) {
+                        sm = res;
+                        l += 1;
+                    }
+                }
+                return l - self.size;
+            }
+            sm = F::M::binary_operation(&sm, &self.d[l]);
+            l += 1;
+            if l & l.wrapping_neg() == l {
+                break;
+            }
+        }
+        self.n
+    }
+
+    /// g(prod(l, r))がtrueになる最小のl．gは単調で，g(単位元)はtrueであること
+    pub fn min_left(&mut self, mut r: usize, g: impl Fn(&<F::M as Monoid>::S) -> bool) -> usize {
+        assert!(r <= self.n);
+        assert!(g(&F::M::identity()));
+        if r == 0 {
+            return 0;
+        }
+        r += self.size;
+        for i in (1..=self.log).rev() {
+            self.push((r - 1) >> i);
+        }
+        let mut sm = F::M::identity();
+        loop {
+            r -= 1;
+            while r > 1 && r & 1 == 1 {
+                r >>= 1;
+            }
+            if !g(&F::M::binary_operation(&self.d[r], &sm)) {
+                while r < self.size {
+                    self.push(r);
+                    r = 2 * r + 1;
+                    let res = F::M::binary_operation(&self.d[r], &sm);
+                    if g(&res) {
+                        sm = res;
+                        r -= 1;
+                    }
+                }
+                return r + 1 - self.size;
+            }
+            sm = F::M::binary_operation(&self.d[r], &sm);
+            if r & r.wrapping_neg() == r {
+                break;
+            }
+        }
+        0
+    }
+}
+#[snippet("r3yohei_LazySegTree")]
+impl<F: MapMonoid> From<Vec<<F::M as Monoid>::S>> for LazySegTree<F> {
+    fn from(v: Vec<<F::M as Monoid>::S>) -> Self {
+        let n = v.len();
+        let mut log = 0;
+        while (1 << log) < n {
+            log += 1;
+        }
+        let size = 1 << log;
+        let mut d = vec![F::M::identity(); 2 * size];
+        d[size..size + n].clone_from_slice(&v);
+        let mut seg = Self {
+            n,
+            size,
+            log,
+            d,
+            lz: vec![F::identity_map(); size],
+        };
+        for k in (1..size).rev() {
+            seg.update(k);
+        }
+        seg
+    }
+}
+
+#[test]
+fn test_lazy_segtree() {
+    use crate::rand::xoshiro256::Xoshiro256;
+
+    let mut rng = Xoshiro256::new(8192);
+    for n in [1, 2, 5, 8, 13] {
+        let mut a = (0..n).map(|_| rng.gen_i64(0, 10)).collect::<Vec<_>>();
+        let mut seg = LazySegTree::<RangeAddRangeMin>::from(a.clone());
+        let mut b = (0..n).map(|_| ModInt::new(rng.gen_usize(0, 10))).collect::<Vec<_>>();
+        let mut seg_affine = LazySegTree::<RangeAffineRangeSum>::from(b.iter().map(|&x| (x, 1)).collect::<Vec<_>>());
+        for _ in 0..300 {
+            let l = rng.gen_usize(0, n + 1);
+            let r = rng.gen_usize(l, n + 1);
+            match rng.gen_usize(0, 4) {
+                0 => {
+                    let x = rng.gen_i64(-5, 6);
+                    seg.apply_range(l, r, x);
+                    for v in a[l..r].iter_mut() {
+                        *v += x;
+                    }
+                    let (c, d) = (ModInt::new(rng.gen_usize(0, 10)), ModInt::new(rng.gen_usize(0, 10)));
+                    seg_affine.apply_range(l, r, (c, d));
+                    for v in b[l..r].iter_mut() {
+                        *v = c * *v + d;
+                    }
+                }
+                1 => {
+                    let p = rng.gen_usize(0, n);
+                    let x = rng.gen_i64(-5, 6);
+                    if rng.gen_bool(0.5) {
+                        seg.apply(p, x);
+                        a[p] += x;
+                    } else {
+                        seg.set(p, x);
+                        a[p] = x;
+                    }
+                    assert_eq!(seg.get(p), a[p]);
+                }
+                2 => {
+                    assert_eq!(seg.prod(l, r), a[l..r].iter().copied().min().unwrap_or(i64::MAX));
+                    let sum = b[l..r].iter().fold(ModInt::zero(), |acc, &x| acc + x);
+                    assert_eq!(seg_affine.prod(l, r).0.value(), sum.value());
+                }
+                _ => {
+                    // 最小値がlimit以上である範囲
+                    let limit = rng.gen_i64(-10, 10);
+                    let expected = (l..=n).take_while(|&r| a[l..r].iter().all(|&x| x >= limit)).last().unwrap();
+                    assert_eq!(seg.max_right(l, |&m| m >= limit), expected);
+                    let expected = (0..=r).rev().take_while(|&l| a[l..r].iter().all(|&x| x >= limit)).last().unwrap();
+                    assert_eq!(seg.min_left(r, |&m| m >= limit), expected);
+                }
+            }
+        }
+        assert_eq!(seg.all_prod(), a.iter().copied().min().unwrap());
+    }
+}
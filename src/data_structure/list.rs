@@ -0,0 +1,206 @@
+use cargo_snippet::snippet;
+
+/// 列を表すリストの共通インターフェース
+/// size, get, set, add, removeだけ実装すれば残りはデフォルト実装で使える
+/// デフォルト実装はget, add, removeを繰り返すだけなので，速くできる実装は上書きする
+#[snippet("r3yohei_list")]
+pub trait List<T: Clone> {
+    fn size(&self) -> usize;
+    fn get(&self, i: usize) -> Option<T>;
+    /// i番目をxにして，元の値を返す
+    fn set(&mut self, i: usize, x: T) -> Option<T>;
+    /// i番目にxを入れる (0 <= i <= size)
+    fn add(&mut self, i: usize, x: T);
+    fn remove(&mut self, i: usize) -> Option<T>;
+
+    fn is_empty(&self) -> bool {
+        self.size() == 0
+    }
+
+    fn push_front(&mut self, x: T) {
+        self.add(0, x);
+    }
+
+    fn push_back(&mut self, x: T) {
+        self.add(self.size(), x);
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        if self.is_empty() { None } else { self.remove(0) }
+    }
+
+    fn pop_back(&mut self) -> Option<T> {
+        if self.is_empty() { None } else { self.remove(self.size() - 1) }
+    }
+
+    /// 前から順にfを呼び，fがfalseを返したらそこで止める
+    fn visit(&self, f: &mut dyn FnMut(&T) -> bool) {
+        for i in 0..self.size() {
+            if !f(&self.get(i).unwrap()) {
+                break;
+            }
+        }
+    }
+
+    fn for_each(&self, mut f: impl FnMut(&T))
+    where
+        Self: Sized,
+    {
+        self.visit(&mut |x| {
+            f(x);
+            true
+        });
+    }
+
+    fn to_vec(&self) -> Vec<T> {
+        let mut res = Vec::with_capacity(self.size());
+        self.visit(&mut |x| {
+            res.push(x.clone());
+            true
+        });
+        res
+    }
+
+    /// predを満たす最初の要素の位置
+    fn position(&self, mut pred: impl FnMut(&T) -> bool) -> Option<usize>
+    where
+        Self: Sized,
+    {
+        let mut i = 0;
+        let mut found = None;
+        self.visit(&mut |x| {
+            if pred(x) {
+                found = Some(i);
+                false
+            } else {
+                i += 1;
+                true
+            }
+        });
+        found
+    }
+
+    /// xと等しい最初の要素の位置
+    fn find(&self, x: &T) -> Option<usize>
+    where
+        T: PartialEq,
+        Self: Sized,
+    {
+        self.position(|y| y == x)
+    }
+
+    /// i番目からxsを順に入れる
+    fn add_all(&mut self, i: usize, xs: Vec<T>) {
+        for (k, x) in xs.into_iter().enumerate() {
+            self.add(i + k, x);
+        }
+    }
+
+    /// [l, r) を取り除いて返す
+    fn remove_range(&mut self, l: usize, r: usize) -> Vec<T> {
+        (l..r).map(|_| self.remove(l).unwrap()).collect()
+    }
+
+    fn clear(&mut self) {
+        while self.pop_back().is_some() {}
+    }
+}
+
+/// Listの実装を同じ乱択操作列でVecと比べる
+#[cfg(test)]
+pub fn check_list<L: List<i64>>(list: &mut L, seed: u64, q: usize) {
+    use crate::rand::xoshiro256::Xoshiro256;
+
+    let mut rng = Xoshiro256::new(seed);
+    let mut naive: Vec<i64> = vec![];
+    for _ in 0..q {
+        let n = naive.len();
+        match rng.gen_usize(0, 12) {
+            0..=2 => {
+                let i = rng.gen_usize(0, n + 1);
+                let x = rng.gen_i64(0, 50);
+                list.add(i, x);
+                naive.insert(i, x);
+            }
+            3 => {
+                let x = rng.gen_i64(0, 50);
+                if rng.gen_bool(0.5) {
+                    list.push_front(x);
+                    naive.insert(0, x);
+                } else {
+                    list.push_back(x);
+                    naive.push(x);
+                }
+            }
+            4 => {
+                if n > 0 {
+                    let i = rng.gen_usize(0, n);
+                    assert_eq!(list.remove(i), Some(naive.remove(i)));
+                }
+            }
+            5 => {
+                if rng.gen_bool(0.5) {
+                    assert_eq!(list.pop_front(), if n > 0 { Some(naive.remove(0)) } else { None });
+                } else {
+                    assert_eq!(list.pop_back(), naive.pop());
+                }
+            }
+            6 => {
+                if n > 0 {
+                    let i = rng.gen_usize(0, n);
+                    assert_eq!(list.get(i), Some(naive[i]));
+                    let x = rng.gen_i64(0, 50);
+                    assert_eq!(list.set(i, x), Some(naive[i]));
+                    naive[i] = x;
+                }
+            }
+            7 => {
+                let x = rng.gen_i64(0, 50);
+                assert_eq!(list.find(&x), naive.iter().position(|&y| y == x));
+                assert_eq!(list.position(|&y| y > x), naive.iter().position(|&y| y > x));
+            }
+            8 => {
+                let i = rng.gen_usize(0, n + 1);
+                let xs = (0..rng.gen_usize(0, 20)).map(|_| rng.gen_i64(0, 50)).collect::<Vec<_>>();
+                list.add_all(i, xs.clone());
+                naive.splice(i..i, xs);
+            }
+            9 => {
+                let l = rng.gen_usize(0, n + 1);
+                let r = rng.gen_usize(l, (l + 20).min(n) + 1);
+                assert_eq!(list.remove_range(l, r), naive.drain(l..r).collect::<Vec<_>>());
+            }
+            10 => {
+                let mut sum = 0;
+                list.for_each(|&x| sum += x);
+                assert_eq!(sum, naive.iter().sum::<i64>());
+                assert_eq!(list.to_vec(), naive);
+            }
+            _ => {
+                if rng.gen_usize(0, 50) == 0 {
+                    list.clear();
+                    naive.clear();
+                }
+            }
+        }
+        assert_eq!(list.size(), naive.len());
+        assert_eq!(list.is_empty(), naive.is_empty());
+    }
+    assert_eq!(list.to_vec(), naive);
+}
+
+#[test]
+fn test_list_backends() {
+    use crate::data_structure::{arena_list::ArenaList, array_list::ArrayList, ddlist::DLList, rope::Rope, skiplist::SkipList};
+    use crate::data_structure::{lazy_segtree::RangeAddRangeMin, treap::ImplicitTreap};
+
+    let q = if cfg!(miri) { 300 } else { 5000 };
+    for seed in 0..3 {
+        check_list(&mut DLList::new(), seed, q);
+        check_list(&mut ArenaList::new(), seed, q);
+        check_list(&mut SkipList::new(), seed, q);
+        check_list(&mut ArrayList::new(), seed, q);
+        check_list(&mut Rope::new(), seed, q);
+        check_list(&mut ImplicitTreap::<RangeAddRangeMin>::new(), seed, q);
+    }
+}
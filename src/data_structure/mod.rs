@@ -1,2 +1,17 @@
 pub mod ddlist;
-// pub mod skiplist;
\ No newline at end of file
+pub mod skiplist;
+pub mod radix_heap;
+pub mod union_find;
+pub mod arena_list;
+pub mod list;
+pub mod array_list;
+pub mod rope;
+pub mod bounded_sorted_list;
+pub mod segtree;
+pub mod lazy_segtree;
+pub mod dynamic_segtree;
+pub mod persistent_segtree;
+pub mod fenwick;
+pub mod treap;
+pub mod sparse_table;
+pub mod indexed_heap;
\ No newline at end of file
@@ -0,0 +1,160 @@
+use cargo_snippet::snippet;
+use crate::data_structure::segtree::Monoid;
+
+/// 永続セグメント木の節点
+#[snippet("r3yohei_PersistentSegTree")]
+#[derive(Clone, Debug)]
+struct PersistentNode<S> {
+    val: S,
+    left: usize,
+    right: usize,
+}
+
+/// 永続セグメント木
+/// 一点更新で根から葉までの節点だけを複製し，新しい版(根の番号)を返す
+/// 版0は初期状態．どの版に対しても更新と区間の積がO(logN)，節点数はO(N + Q logN)
+#[snippet("r3yohei_PersistentSegTree")]
+#[snippet(include = "r3yohei_Monoid")]
+pub struct PersistentSegTree<M: Monoid> {
+    n: usize,
+    nodes: Vec<PersistentNode<M::S>>,
+    roots: Vec<usize>,
+}
+#[snippet("r3yohei_PersistentSegTree")]
+impl<M: Monoid> PersistentSegTree<M> {
+    pub fn new(n: usize) -> Self {
+        Self::from(vec![M::identity(); n])
+    }
+
+    // vから木を作り，根の番号を返す
+    fn build(&mut self, v: &[M::S]) -> usize {
+        if v.len() == 1 {
+            self.nodes.push(PersistentNode { val: v[0].clone(), left: !0, right: !0 });
+            return self.nodes.len() - 1;
+        }
+        let m = v.len() / 2;
+        let left = self.build(&v[..m]);
+        let right = self.build(&v[m..]);
+        let val = M::binary_operation(&self.nodes[left].val, &self.nodes[right].val);
+        self.nodes.push(PersistentNode { val, left, right });
+        self.nodes.len() - 1
+    }
+
+    /// 版の数
+    pub fn versions(&self) -> usize {
+        self.roots.len()
+    }
+
+    /// 版versionのp番目をxにした版を作り，その番号を返す
+    pub fn set(&mut self, version: usize, p: usize, x: M::S) -> usize {
+        assert!(p < self.n);
+        let mut path = vec![];
+        let (mut v, mut l, mut r) = (self.roots[version], 0, self.n);
+        while r - l > 1 {
+            let m = l + (r - l) / 2;
+            path.push((v, p < m));
+            if p < m {
+                v = self.nodes[v].left;
+                r = m;
+            } else {
+                v = self.nodes[v].right;
+                l = m;
+            }
+        }
+        self.nodes.push(PersistentNode { val: x, left: !0, right: !0 });
+        let mut child = self.nodes.len() - 1;
+        // 葉から根に向かって，通った節点を複製していく
+        for &(v, to_left) in path.iter().rev() {
+            let (left, right) = if to_left { (child, self.nodes[v].right) } else { (self.nodes[v].left, child) };
+            let val = M::binary_operation(&self.nodes[left].val, &self.nodes[right].val);
+            self.nodes.push(PersistentNode { val, left, right });
+            child = self.nodes.len() - 1;
+        }
+        self.roots.push(child);
+        self.roots.len() - 1
+    }
+
+    pub fn get(&self, version: usize, p: usize) -> M::S {
+        assert!(p < self.n);
+        let (mut v, mut l, mut r) = (self.roots[version], 0, self.n);
+        while r - l > 1 {
+            let m = l + (r - l) / 2;
+            if p < m {
+                v = self.nodes[v].left;
+                r = m;
+            } else {
+                v = self.nodes[v].right;
+                l = m;
+            }
+        }
+        self.nodes[v].val.clone()
+    }
+
+    /// 版versionの[l, r) の積
+    pub fn prod(&self, version: usize, l: usize, r: usize) -> M::S {
+        assert!(l <= r && r <= self.n);
+        if self.n == 0 {
+            return M::identity();
+        }
+        self.prod_rec(self.roots[version], 0, self.n, l, r)
+    }
+
+    fn prod_rec(&self, v: usize, vl: usize, vr: usize, l: usize, r: usize) -> M::S {
+        if r <= vl || vr <= l {
+            return M::identity();
+        }
+        if l <= vl && vr <= r {
+            return self.nodes[v].val.clone();
+        }
+        let m = vl + (vr - vl) / 2;
+        M::binary_operation(
+            &self.prod_rec(self.nodes[v].left, vl, m, l, r),
+            &self.prod_rec(self.nodes[v].right, m, vr, l, r),
+        )
+    }
+}
+#[snippet("r3yohei_PersistentSegTree")]
+impl<M: Monoid> From<Vec<M::S>> for PersistentSegTree<M> {
+    fn from(v: Vec<M::S>) -> Self {
+        let mut seg = Self { n: v.len(), nodes: vec![], roots: vec![] };
+        if !v.is_empty() {
+            let root = seg.build(&v);
+            seg.roots.push(root);
+        } else {
+            seg.roots.push(!0);
+        }
+        seg
+    }
+}
+
+#[test]
+fn test_persistent_segtree() {
+    use crate::data_structure::segtree::Additive;
+    use crate::rand::xoshiro256::Xoshiro256;
+
+    let mut rng = Xoshiro256::new(8192);
+    for n in [1, 2, 7, 16] {
+        let a = (0..n).map(|_| rng.gen_i64(0, 10)).collect::<Vec<_>>();
+        let mut seg = PersistentSegTree::<Additive<i64>>::from(a.clone());
+        let mut history = vec![a];
+        for _ in 0..200 {
+            // 過去の適当な版から分岐させる
+            let version = rng.gen_usize(0, history.len());
+            let p = rng.gen_usize(0, n);
+            let x = rng.gen_i64(0, 10);
+            let mut b = history[version].clone();
+            b[p] = x;
+            assert_eq!(seg.set(version, p, x), history.len());
+            history.push(b);
+
+            let version = rng.gen_usize(0, history.len());
+            let l = rng.gen_usize(0, n + 1);
+            let r = rng.gen_usize(l, n + 1);
+            assert_eq!(seg.prod(version, l, r), history[version][l..r].iter().sum::<i64>());
+            let p = rng.gen_usize(0, n);
+            assert_eq!(seg.get(version, p), history[version][p]);
+        }
+        assert_eq!(seg.versions(), history.len());
+    }
+    assert_eq!(PersistentSegTree::<Additive<i64>>::new(0).prod(0, 0, 0), 0);
+}
@@ -0,0 +1,84 @@
+use cargo_snippet::snippet;
+
+/// 基数ヒープ
+/// 最後に取り出したキー以上のキーしか入れない(単調)場合に使える優先度付きキュー
+/// キーはu64で，push O(1)，pop ならしO(log(キーの最大値))
+#[snippet("r3yohei_radix_heap")]
+#[derive(Clone, Debug)]
+pub struct RadixHeap<V> {
+    // bucket[i]には，lastとのxorの最上位bitがi-1であるキーを入れる(bucket[0]はlastと等しいキー)
+    bucket: Vec<Vec<(u64, V)>>,
+    last: u64,
+    len: usize,
+}
+#[snippet("r3yohei_radix_heap")]
+impl<V> RadixHeap<V> {
+    pub fn new() -> Self {
+        Self {
+            bucket: (0..65).map(|_| vec![]).collect(),
+            last: 0,
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn bucket_id(&self, key: u64) -> usize {
+        64 - (key ^ self.last).leading_zeros() as usize
+    }
+
+    pub fn push(&mut self, key: u64, value: V) {
+        assert!(self.last <= key, "key must not be less than the last popped key");
+        let i = self.bucket_id(key);
+        self.bucket[i].push((key, value));
+        self.len += 1;
+    }
+
+    pub fn pop(&mut self) -> Option<(u64, V)> {
+        if self.len == 0 {
+            return None;
+        }
+        if self.bucket[0].is_empty() {
+            // 空でない最初のbucketの最小キーを新しいlastとして，中身を下位のbucketへ振り分け直す
+            let i = (1..65).find(|&i| !self.bucket[i].is_empty()).unwrap();
+            self.last = self.bucket[i].iter().map(|&(key, _)| key).min().unwrap();
+            let moved = std::mem::take(&mut self.bucket[i]);
+            for (key, value) in moved {
+                let j = self.bucket_id(key);
+                self.bucket[j].push((key, value));
+            }
+        }
+        self.len -= 1;
+        self.bucket[0].pop()
+    }
+}
+#[snippet("r3yohei_radix_heap")]
+impl<V> Default for RadixHeap<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[test]
+fn test_radix_heap() {
+    let mut heap = RadixHeap::new();
+    heap.push(5, 'a');
+    heap.push(1, 'b');
+    heap.push(8, 'c');
+    assert_eq!(heap.pop(), Some((1, 'b')));
+    heap.push(3, 'd');
+    heap.push(1, 'e');
+    assert_eq!(heap.pop(), Some((1, 'e')));
+    assert_eq!(heap.pop(), Some((3, 'd')));
+    assert_eq!(heap.pop(), Some((5, 'a')));
+    assert_eq!(heap.len(), 1);
+    assert_eq!(heap.pop(), Some((8, 'c')));
+    assert_eq!(heap.pop(), None);
+    assert!(heap.is_empty());
+}
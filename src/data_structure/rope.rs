@@ -0,0 +1,214 @@
+use cargo_snippet::snippet;
+use crate::data_structure::list::List;
+
+/// チャンクの大きさの目安．チャンクがこの2倍を超えたら分ける
+#[snippet("r3yohei_rope")]
+const CHUNK: usize = 256;
+
+/// 長さCHUNK程度の配列を並べたロープ(平方分割によるリスト)
+/// i番目の参照･挿入･削除がO(N/CHUNK + CHUNK)，区間の挿入･削除もチャンク単位で動かす
+/// 空のチャンクは持たない
+#[snippet("r3yohei_rope")]
+#[derive(Clone, Debug, Default)]
+pub struct Rope<T> {
+    chunks: Vec<Vec<T>>,
+    len: usize,
+}
+#[snippet("r3yohei_rope")]
+impl<T> Rope<T> {
+    pub fn new() -> Self {
+        Self { chunks: vec![], len: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // i番目の要素が(何番目のチャンクの, 何番目か)．i == lenなら最後のチャンクの末尾
+    fn locate(&self, mut i: usize) -> (usize, usize) {
+        for (c, chunk) in self.chunks.iter().enumerate() {
+            if i < chunk.len() {
+                return (c, i);
+            }
+            i -= chunk.len();
+        }
+        let c = self.chunks.len().saturating_sub(1);
+        (c, self.chunks.get(c).map_or(0, |chunk| chunk.len()))
+    }
+
+    // i番目の前がチャンクの境目になるように分け，i番目から始まるチャンクの番号を返す
+    fn split_at(&mut self, mut i: usize) -> usize {
+        for c in 0..self.chunks.len() {
+            if i == 0 {
+                return c;
+            }
+            if i < self.chunks[c].len() {
+                let tail = self.chunks[c].split_off(i);
+                self.chunks.insert(c + 1, tail);
+                return c + 1;
+            }
+            i -= self.chunks[c].len();
+        }
+        self.chunks.len()
+    }
+
+    // 区間操作で小さいチャンクが増えすぎたら作り直す
+    fn rebuild_if_fragmented(&mut self) {
+        if self.chunks.len() <= 2 * (self.len / CHUNK + 1) + 8 {
+            return;
+        }
+        let mut chunks = vec![];
+        let mut crt = Vec::with_capacity(CHUNK);
+        for x in std::mem::take(&mut self.chunks).into_iter().flatten() {
+            crt.push(x);
+            if crt.len() == CHUNK {
+                chunks.push(std::mem::replace(&mut crt, Vec::with_capacity(CHUNK)));
+            }
+        }
+        if !crt.is_empty() {
+            chunks.push(crt);
+        }
+        self.chunks = chunks;
+    }
+
+    pub fn get(&self, i: usize) -> Option<&T> {
+        if i >= self.len {
+            return None;
+        }
+        let (c, j) = self.locate(i);
+        Some(&self.chunks[c][j])
+    }
+
+    pub fn get_mut(&mut self, i: usize) -> Option<&mut T> {
+        if i >= self.len {
+            return None;
+        }
+        let (c, j) = self.locate(i);
+        Some(&mut self.chunks[c][j])
+    }
+
+    pub fn insert(&mut self, i: usize, x: T) {
+        assert!(i <= self.len, "index out of bounds");
+        self.len += 1;
+        if self.chunks.is_empty() {
+            self.chunks.push(vec![x]);
+            return;
+        }
+        let (c, j) = self.locate(i);
+        self.chunks[c].insert(j, x);
+        if self.chunks[c].len() > 2 * CHUNK {
+            let tail = self.chunks[c].split_off(CHUNK);
+            self.chunks.insert(c + 1, tail);
+        }
+    }
+
+    pub fn remove(&mut self, i: usize) -> T {
+        assert!(i < self.len, "index out of bounds");
+        self.len -= 1;
+        let (c, j) = self.locate(i);
+        let x = self.chunks[c].remove(j);
+        if self.chunks[c].is_empty() {
+            self.chunks.remove(c);
+        }
+        x
+    }
+
+    /// i番目からxsを順に入れる
+    pub fn insert_many(&mut self, i: usize, xs: Vec<T>) {
+        assert!(i <= self.len, "index out of bounds");
+        if xs.is_empty() {
+            return;
+        }
+        self.len += xs.len();
+        let c = self.split_at(i);
+        let mut xs = xs.into_iter();
+        let mut new_chunks = vec![];
+        loop {
+            let chunk = xs.by_ref().take(CHUNK).collect::<Vec<_>>();
+            if chunk.is_empty() {
+                break;
+            }
+            new_chunks.push(chunk);
+        }
+        self.chunks.splice(c..c, new_chunks);
+        self.rebuild_if_fragmented();
+    }
+
+    /// [l, r) を取り除いて返す
+    pub fn drain(&mut self, l: usize, r: usize) -> Vec<T> {
+        assert!(l <= r && r <= self.len, "range out of bounds");
+        self.len -= r - l;
+        let cl = self.split_at(l);
+        let cr = self.split_at(r);
+        let removed = self.chunks.drain(cl..cr).flatten().collect();
+        self.rebuild_if_fragmented();
+        removed
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> + '_ {
+        self.chunks.iter().flatten()
+    }
+}
+#[snippet("r3yohei_rope")]
+impl<T> std::iter::FromIterator<T> for Rope<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut rope = Self::new();
+        rope.insert_many(0, iter.into_iter().collect());
+        rope
+    }
+}
+#[snippet("r3yohei_rope")]
+#[snippet(include = "r3yohei_list")]
+impl<T: Clone> List<T> for Rope<T> {
+    fn size(&self) -> usize {
+        self.len
+    }
+    fn get(&self, i: usize) -> Option<T> {
+        Rope::get(self, i).cloned()
+    }
+    fn set(&mut self, i: usize, x: T) -> Option<T> {
+        self.get_mut(i).map(|y| std::mem::replace(y, x))
+    }
+    fn add(&mut self, i: usize, x: T) {
+        self.insert(i, x);
+    }
+    fn remove(&mut self, i: usize) -> Option<T> {
+        if i < self.len { Some(Rope::remove(self, i)) } else { None }
+    }
+    fn visit(&self, f: &mut dyn FnMut(&T) -> bool) {
+        for x in self.iter() {
+            if !f(x) {
+                break;
+            }
+        }
+    }
+    fn add_all(&mut self, i: usize, xs: Vec<T>) {
+        self.insert_many(i, xs);
+    }
+    fn remove_range(&mut self, l: usize, r: usize) -> Vec<T> {
+        self.drain(l, r)
+    }
+    fn clear(&mut self) {
+        self.chunks.clear();
+        self.len = 0;
+    }
+}
+
+#[test]
+fn test_rope() {
+    // 編集操作: 区間を切り取って別の場所に貼り付ける
+    let mut rope = (0..1000).collect::<Rope<_>>();
+    let cut = rope.drain(100, 600);
+    rope.insert_many(200, cut);
+    let mut naive = (0..1000).collect::<Vec<_>>();
+    let cut = naive.drain(100..600).collect::<Vec<_>>();
+    naive.splice(200..200, cut);
+    assert_eq!(rope.iter().copied().collect::<Vec<_>>(), naive);
+    assert_eq!(rope.iter().next_back(), Some(&999));
+    assert_eq!(rope.get(250), Some(&150));
+    assert_eq!(rope.len(), 1000);
+}
@@ -0,0 +1,311 @@
+use cargo_snippet::snippet;
+use crate::util::modint::ModInt;
+
+#[snippet("r3yohei_Monoid")]
+use std::marker::PhantomData;
+
+/// モノイド (結合則を満たす演算と単位元)
+#[snippet("r3yohei_Monoid")]
+pub trait Monoid {
+    type S: Clone;
+    fn identity() -> Self::S;
+    fn binary_operation(a: &Self::S, b: &Self::S) -> Self::S;
+}
+
+/// 最小値･最大値の単位元に使う，型の最大値と最小値
+#[snippet("r3yohei_Monoid")]
+pub trait BoundedValue: Copy + Ord {
+    fn min_value() -> Self;
+    fn max_value() -> Self;
+}
+#[snippet("r3yohei_Monoid")]
+macro_rules! impl_bounded_value {
+    ($($t:ty),*) => {
+        $(impl BoundedValue for $t {
+            fn min_value() -> Self {
+                <$t>::MIN
+            }
+            fn max_value() -> Self {
+                <$t>::MAX
+            }
+        })*
+    };
+}
+#[snippet("r3yohei_Monoid")]
+impl_bounded_value!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+/// 和．単位元はDefault (数値なら0，ModIntでも使える)
+#[snippet("r3yohei_Monoid")]
+pub struct Additive<T>(PhantomData<T>);
+#[snippet("r3yohei_Monoid")]
+impl<T: Copy + Default + std::ops::Add<Output = T>> Monoid for Additive<T> {
+    type S = T;
+    fn identity() -> T {
+        T::default()
+    }
+    fn binary_operation(a: &T, b: &T) -> T {
+        *a + *b
+    }
+}
+
+/// 最小値
+#[snippet("r3yohei_Monoid")]
+pub struct Min<T>(PhantomData<T>);
+#[snippet("r3yohei_Monoid")]
+impl<T: BoundedValue> Monoid for Min<T> {
+    type S = T;
+    fn identity() -> T {
+        T::max_value()
+    }
+    fn binary_operation(a: &T, b: &T) -> T {
+        *a.min(b)
+    }
+}
+
+/// 最大値
+#[snippet("r3yohei_Monoid")]
+pub struct Max<T>(PhantomData<T>);
+#[snippet("r3yohei_Monoid")]
+impl<T: BoundedValue> Monoid for Max<T> {
+    type S = T;
+    fn identity() -> T {
+        T::min_value()
+    }
+    fn binary_operation(a: &T, b: &T) -> T {
+        *a.max(b)
+    }
+}
+
+/// 最大公約数．単位元は0
+#[snippet("r3yohei_Monoid")]
+pub struct Gcd;
+#[snippet("r3yohei_Monoid")]
+impl Monoid for Gcd {
+    type S = u64;
+    fn identity() -> u64 {
+        0
+    }
+    fn binary_operation(&a: &u64, &b: &u64) -> u64 {
+        let (mut a, mut b) = (a, b);
+        while b != 0 {
+            (a, b) = (b, a % b);
+        }
+        a
+    }
+}
+
+/// 一次関数 x -> ax + b の合成．(a, b)で表し，左のものから順に適用する
+#[snippet("r3yohei_Affine")]
+#[snippet(include = "r3yohei_Monoid")]
+#[snippet(include = "r3yohei_ModInt")]
+pub struct Affine;
+#[snippet("r3yohei_Affine")]
+impl Monoid for Affine {
+    type S = (ModInt, ModInt);
+    fn identity() -> Self::S {
+        (ModInt::one(), ModInt::zero())
+    }
+    fn binary_operation(f: &Self::S, g: &Self::S) -> Self::S {
+        // fの後にg
+        (f.0 * g.0, f.1 * g.0 + g.1)
+    }
+}
+
+/// セグメント木
+/// 一点更新と区間の積(モノイドの演算でまとめたもの)がO(logN)
+#[snippet("r3yohei_SegTree")]
+#[snippet(include = "r3yohei_Monoid")]
+pub struct SegTree<M: Monoid> {
+    n: usize,
+    size: usize,
+    log: usize,
+    d: Vec<M::S>,
+}
+#[snippet("r3yohei_SegTree")]
+impl<M: Monoid> SegTree<M> {
+    pub fn new(n: usize) -> Self {
+        Self::from(vec![M::identity(); n])
+    }
+
+    fn update(&mut self, k: usize) {
+        self.d[k] = M::binary_operation(&self.d[2 * k], &self.d[2 * k + 1]);
+    }
+
+    pub fn set(&mut self, mut p: usize, x: M::S) {
+        assert!(p < self.n);
+        p += self.size;
+        self.d[p] = x;
+        for i in 1..=self.log {
+            self.update(p >> i);
+        }
+    }
+
+    pub fn get(&self, p: usize) -> M::S {
+        assert!(p < self.n);
+        self.d[p + self.size].clone()
+    }
+
+    /// [l, r) の積
+    pub fn prod(&self, mut l: usize, mut r: usize) -> M::S {
+        assert!(l <= r && r <= self.n);
+        let mut sml = M::identity();
+        let mut smr = M::identity();
+        l += self.size;
+        r += self.size;
+        while l < r {
+            if l & 1 == 1 {
+                sml = M::binary_operation(&sml, &self.d[l]);
+                l += 1;
+            }
+            if r & 1 == 1 {
+                r -= 1;
+                smr = M::binary_operation(&self.d[r], &smr);
+            }
+            l >>= 1;
+            r >>= 1;
+        }
+        M::binary_operation(&sml, &smr)
+    }
+
+    pub fn all_prod(&self) -> M::S {
+        self.d[1].clone()
+    }
+
+    /// f(prod(l, r))がtrueになる最大のr．fは単調で，f(単位元)はtrueであること
+    pub fn max_right(&self, mut l: usize, f: impl Fn(&M::S) -> bool) -> usize {
+        assert!(l <= self.n);
+        assert!(f(&M::identity()));
+        if l == self.n {
+            return self.n;
+        }
+        l += self.size;
+        let mut sm = M::identity();
+        loop {
+            while l & 1 == 0 {
+                l >>= 1;
+            }
+            if !f(&M::binary_operation(&sm, &self.d[l])) {
+                // ここから葉まで下りる
+                while l < self.size {
+                    l *= 2;
+                    let res = M::binary_operation(&sm, &self.d[l]);
+                    if f(&res) {
+                        sm = res;
+                        l += 1;
+                    }
+                }
+                return l - self.size;
+            }
+            sm = M::binary_operation(&sm, &self.d[l]);
+            l += 1;
+            if l & l.wrapping_neg() == l {
+                break;
+            }
+        }
+        self.n
+    }
+
+    /// f(prod(l, r))がtrueになる最小のl．fは単調で，f(単位元)はtrueであること
+    pub fn min_left(&self, mut r: usize, f: impl Fn(&M::S) -> bool) -> usize {
+        assert!(r <= self.n);
+        assert!(f(&M::identity()));
+        if r == 0 {
+            return 0;
+        }
+        r += self.size;
+        let mut sm = M::identity();
+        loop {
+            r -= 1;
+            while r > 1 && r & 1 == 1 {
+                r >>= 1;
+            }
+            if !f(&M::binary_operation(&self.d[r], &sm)) {
+                while r < self.size {
+                    r = 2 * r + 1;
+                    let res = M::binary_operation(&self.d[r], &sm);
+                    if f(&res) {
+                        sm = res;
+                        r -= 1;
+                    }
+                }
+                return r + 1 - self.size;
+            }
+            sm = M::binary_operation(&self.d[r], &sm);
+            if r & r.wrapping_neg() == r {
+                break;
+            }
+        }
+        0
+    }
+}
+#[snippet("r3yohei_SegTree")]
+impl<M: Monoid> From<Vec<M::S>> for SegTree<M> {
+    fn from(v: Vec<M::S>) -> Self {
+        let n = v.len();
+        let mut log = 0;
+        while (1 << log) < n {
+            log += 1;
+        }
+        let size = 1 << log;
+        let mut d = vec![M::identity(); 2 * size];
+        d[size..size + n].clone_from_slice(&v);
+        let mut seg = Self { n, size, log, d };
+        for k in (1..size).rev() {
+            seg.update(k);
+        }
+        seg
+    }
+}
+
+#[test]
+fn test_segtree() {
+    use crate::rand::xoshiro256::Xoshiro256;
+
+    let mut rng = Xoshiro256::new(8192);
+    for n in [0, 1, 2, 5, 8, 13] {
+        let mut a = (0..n).map(|_| rng.gen_i64(0, 10)).collect::<Vec<_>>();
+        let mut sum = SegTree::<Additive<i64>>::from(a.clone());
+        let mut min = SegTree::<Min<i64>>::from(a.clone());
+        let mut max = SegTree::<Max<i64>>::new(n);
+        for (i, &x) in a.iter().enumerate() {
+            max.set(i, x);
+        }
+        for _ in 0..200 {
+            if n > 0 && rng.gen_bool(0.5) {
+                let p = rng.gen_usize(0, n);
+                a[p] = rng.gen_i64(0, 10);
+                sum.set(p, a[p]);
+                min.set(p, a[p]);
+                max.set(p, a[p]);
+                assert_eq!(sum.get(p), a[p]);
+            }
+            let l = rng.gen_usize(0, n + 1);
+            let r = rng.gen_usize(l, n + 1);
+            assert_eq!(sum.prod(l, r), a[l..r].iter().sum::<i64>());
+            assert_eq!(min.prod(l, r), a[l..r].iter().copied().min().unwrap_or(i64::MAX));
+            assert_eq!(max.prod(l, r), a[l..r].iter().copied().max().unwrap_or(i64::MIN));
+            // 和がlimit以下になる範囲
+            let limit = rng.gen_i64(0, 30);
+            let expected = (l..=n).take_while(|&r| a[l..r].iter().sum::<i64>() <= limit).last().unwrap();
+            assert_eq!(sum.max_right(l, |&s| s <= limit), expected);
+            let expected = (0..=r).rev().take_while(|&l| a[l..r].iter().sum::<i64>() <= limit).last().unwrap();
+            assert_eq!(sum.min_left(r, |&s| s <= limit), expected);
+        }
+        assert_eq!(sum.all_prod(), a.iter().sum::<i64>());
+    }
+}
+
+#[test]
+fn test_affine() {
+    // x -> 2x + 3，x -> 5x + 1 の順に適用すると x -> 10x + 16
+    let fs = vec![
+        (ModInt::new(2), ModInt::new(3)),
+        (ModInt::new(5), ModInt::new(1)),
+        (ModInt::new(7), ModInt::new(0)),
+    ];
+    let seg = SegTree::<Affine>::from(fs);
+    let (a, b) = seg.prod(0, 2);
+    assert_eq!((a.value(), b.value()), (10, 16));
+    let (a, b) = seg.all_prod();
+    assert_eq!((a.value(), b.value()), (70, 112));
+}
@@ -1,65 +1,66 @@
 use cargo_snippet::snippet;
+use crate::data_structure::list::List;
+use crate::rand::xoshiro256::Xoshiro256;
 
-#[snippet("r3yohei_skiplist")]
-use rand::Rng;
 #[snippet("r3yohei_skiplist")]
 use std::{
-    alloc::{alloc, dealloc, Layout},
     fmt::Debug,
-    ops::Index,
-    ptr::{null_mut, read, write},
+    iter::FromIterator,
+    marker::PhantomData,
+    ops::{Bound, Index, IndexMut, RangeBounds},
+    ptr::null_mut,
 };
 
 #[snippet("r3yohei_skiplist")]
 const MAX_LEVEL: usize = 20;
 
+/// スキップリストのノード
+/// 先頭の番兵だけvalueがNone．skip[l]はレベルlで次のノードまでに進む要素数
+/// prevはレベル0での前のノードで，最初の要素ではnull (番兵は指さない)
 #[snippet("r3yohei_skiplist")]
 struct Node<T> {
-    value: *mut T,
+    value: Option<T>,
     next: [*mut Self; MAX_LEVEL],
     skip: [usize; MAX_LEVEL],
+    prev: *mut Self,
 }
 #[snippet("r3yohei_skiplist")]
 impl<T> Node<T> {
-    const LAYOUT: Layout = Layout::new::<Self>();
-    const LAYOUT_T: Layout = Layout::new::<T>();
-
-    fn alloc(value: T) -> *mut Self {
-        unsafe {
-            let ptr = alloc(Self::LAYOUT) as *mut Self;
-            (*ptr).value = alloc(Self::LAYOUT_T) as *mut T;
-            write((*ptr).value, value);
-            ptr
-        }
+    fn alloc(value: Option<T>) -> *mut Self {
+        Box::into_raw(Box::new(Self {
+            value,
+            next: [null_mut(); MAX_LEVEL],
+            skip: [1; MAX_LEVEL],
+            prev: null_mut(),
+        }))
     }
 
-    fn dealloc(ptr: *mut Self) -> T {
-        unsafe {
-            let value = read(&*((*ptr).value));
-            dealloc((*ptr).value as *mut u8, Self::LAYOUT_T);
-            dealloc(ptr as *mut u8, Self::LAYOUT);
-            value
-        }
+    unsafe fn dealloc(ptr: *mut Self) -> Option<T> {
+        Box::from_raw(ptr).value
     }
 }
 
+/// スキップリスト
+/// 位置を指定した挿入･削除･参照が期待O(logN)
+/// レベルはシード付きのXoshiro256で決めるので，同じシードなら同じ形になる
 #[snippet("r3yohei_skiplist")]
+#[snippet(include = "r3yohei_Xoshiro256")]
 pub struct SkipList<T> {
-    head: Node<T>,
+    head: *mut Node<T>,
     len: usize,
-    rng: rand::rngs::ThreadRng,
+    rng: Xoshiro256,
 }
 #[snippet("r3yohei_skiplist")]
 impl<T> SkipList<T> {
     pub fn new() -> Self {
+        Self::with_seed(0)
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
         SkipList {
-            head: Node {
-                value: null_mut(),
-                next: [null_mut(); MAX_LEVEL],
-                skip: [1; MAX_LEVEL],
-            },
+            head: Node::alloc(None),
             len: 0,
-            rng: rand::thread_rng(),
+            rng: Xoshiro256::new(seed),
         }
     }
 
@@ -67,6 +68,10 @@ impl<T> SkipList<T> {
         self.len
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
     fn gen_level(&mut self) -> usize {
         let mut level = 1;
         while self.rng.gen_bool(0.5) && level < MAX_LEVEL {
@@ -82,10 +87,10 @@ impl<T> SkipList<T> {
 
         self.len += 1;
 
-        let new_node = Node::alloc(element);
+        let new_node = Node::alloc(Some(element));
         let new_level = self.gen_level();
 
-        let mut cur = &mut self.head as *mut Node<T>;
+        let mut cur = self.head;
 
         for l in (0..MAX_LEVEL).rev() {
             unsafe {
@@ -98,6 +103,12 @@ impl<T> SkipList<T> {
                     (*cur).next[l] = new_node;
                     (*new_node).skip[l] = (*cur).skip[l] - index;
                     (*cur).skip[l] = index + 1;
+                    if l == 0 {
+                        (*new_node).prev = self.prev_of(cur);
+                        if !(*new_node).next[0].is_null() {
+                            (*(*new_node).next[0]).prev = new_node;
+                        }
+                    }
                 } else {
                     (*cur).skip[l] += 1;
                 }
@@ -112,7 +123,7 @@ impl<T> SkipList<T> {
 
         self.len -= 1;
 
-        let mut cur = &mut self.head as *mut Node<T>;
+        let mut cur = self.head;
         index += 1;
 
         for l in (0..MAX_LEVEL).rev() {
@@ -126,7 +137,10 @@ impl<T> SkipList<T> {
                     (*cur).next[l] = (*next).next[l];
                     (*cur).skip[l] += (*next).skip[l] - 1;
                     if l == 0 {
-                        return Node::dealloc(next);
+                        if !(*next).next[0].is_null() {
+                            (*(*next).next[0]).prev = self.prev_of(cur);
+                        }
+                        return Node::dealloc(next).unwrap();
                     }
                 } else {
                     (*cur).skip[l] -= 1;
@@ -161,22 +175,206 @@ impl<T> SkipList<T> {
         }
     }
 
-    pub fn iter(&self) -> Iter<T> {
+    // curの後ろに入るノードのprev．番兵はnullにする
+    fn prev_of(&self, cur: *mut Node<T>) -> *mut Node<T> {
+        if cur == self.head { null_mut() } else { cur }
+    }
+
+    // index番目(0始まり)のノード
+    fn node_at(&self, mut index: usize) -> *mut Node<T> {
+        let mut cur = self.head;
+        index += 1;
+
+        for l in (0..MAX_LEVEL).rev() {
+            unsafe {
+                while (*cur).skip[l] <= index {
+                    index -= (*cur).skip[l];
+                    cur = (*cur).next[l];
+                }
+            }
+        }
+
+        cur
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        self.get(self.len.wrapping_sub(1))
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.len {
+            return None;
+        }
+        unsafe { (*self.node_at(index)).value.as_ref() }
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index >= self.len {
+            return None;
+        }
+        unsafe { (*self.node_at(index)).value.as_mut() }
+    }
+
+    /// 先頭からpredを満たす要素の個数 (predを満たす要素が前に固まっていること)
+    /// 値の順に並んでいれば，lower_bound(x)はpartition_point(|v| v < x)になる
+    pub fn partition_point(&self, mut pred: impl FnMut(&T) -> bool) -> usize {
+        let mut cur = self.head;
+        let mut index = 0;
+
+        for l in (0..MAX_LEVEL).rev() {
+            unsafe {
+                loop {
+                    let next = (*cur).next[l];
+                    if next.is_null() || !pred((*next).value.as_ref().unwrap()) {
+                        break;
+                    }
+                    index += (*cur).skip[l];
+                    cur = next;
+                }
+            }
+        }
+
+        index
+    }
+
+    /// [index, len) を切り離して返す O(logN)
+    pub fn split_off(&mut self, index: usize) -> Self {
+        if index > self.len {
+            panic!("index out of bounds");
+        }
+
+        let mut other = Self::with_seed(self.rng.next_u64());
+        let mut cur = self.head;
+        let mut rest = index;
+
+        for l in (0..MAX_LEVEL).rev() {
+            unsafe {
+                while (*cur).skip[l] <= rest {
+                    rest -= (*cur).skip[l];
+                    cur = (*cur).next[l];
+                }
+                // curはindex番目以前で，(*cur).next[l]からが切り離す側
+                (*other.head).next[l] = (*cur).next[l];
+                (*other.head).skip[l] = (*cur).skip[l] - rest;
+                (*cur).next[l] = null_mut();
+                (*cur).skip[l] = rest + 1;
+            }
+        }
+        unsafe {
+            if !(*other.head).next[0].is_null() {
+                (*(*other.head).next[0]).prev = null_mut();
+            }
+        }
+
+        other.len = self.len - index;
+        self.len = index;
+        other
+    }
+
+    /// otherの要素をすべて末尾に移す．otherは空になる O(logN)
+    pub fn append(&mut self, other: &mut Self) {
+        let mut cur = self.head;
+        let mut pos = 0;
+
+        for l in (0..MAX_LEVEL).rev() {
+            unsafe {
+                // レベルlでの最後のノードまで進む
+                while !(*cur).next[l].is_null() {
+                    pos += (*cur).skip[l];
+                    cur = (*cur).next[l];
+                }
+                (*cur).next[l] = (*other.head).next[l];
+                (*cur).skip[l] = self.len - pos + (*other.head).skip[l];
+                (*other.head).next[l] = null_mut();
+                (*other.head).skip[l] = 1;
+            }
+        }
+        unsafe {
+            if !(*cur).next[0].is_null() {
+                (*(*cur).next[0]).prev = self.prev_of(cur);
+            }
+        }
+
+        self.len += other.len;
+        other.len = 0;
+    }
+
+    // RangeBoundsを半開区間[l, r)にする
+    fn bounds(&self, range: impl RangeBounds<usize>) -> (usize, usize) {
+        let l = match range.start_bound() {
+            Bound::Included(&l) => l,
+            Bound::Excluded(&l) => l + 1,
+            Bound::Unbounded => 0,
+        };
+        let r = match range.end_bound() {
+            Bound::Included(&r) => r + 1,
+            Bound::Excluded(&r) => r,
+            Bound::Unbounded => self.len,
+        };
+        if l > r || r > self.len {
+            panic!("range out of bounds");
+        }
+        (l, r)
+    }
+
+    /// rangeの要素を取り除き，それらを順に返すイテレータにする O(logN)
+    pub fn drain(&mut self, range: impl RangeBounds<usize>) -> IntoIter<T> {
+        let (l, r) = self.bounds(range);
+        let mut tail = self.split_off(r);
+        let mid = self.split_off(l);
+        self.append(&mut tail);
+        mid.into_iter()
+    }
+
+    /// rangeの要素を順に参照するイテレータ．両端から進められる
+    pub fn range(&self, range: impl RangeBounds<usize>) -> Iter<'_, T> {
+        let (l, r) = self.bounds(range);
+        if l == r {
+            return Iter { front: null_mut(), back: null_mut(), len: 0, _marker: PhantomData };
+        }
         Iter {
-            _target: self,
-            cur: &self.head,
+            front: self.node_at(l),
+            back: self.node_at(r - 1),
+            len: r - l,
+            _marker: PhantomData,
         }
     }
 
-    pub fn iter_mut(&mut self) -> IterMut<T> {
-        let cur = &mut self.head as *mut Node<T>;
-        IterMut { _target: self, cur }
+    pub fn range_mut(&mut self, range: impl RangeBounds<usize>) -> IterMut<'_, T> {
+        let (l, r) = self.bounds(range);
+        if l == r {
+            return IterMut { front: null_mut(), back: null_mut(), len: 0, _marker: PhantomData };
+        }
+        IterMut {
+            front: self.node_at(l),
+            back: self.node_at(r - 1),
+            len: r - l,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.range(..)
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        self.range_mut(..)
+    }
+}
+#[snippet("r3yohei_skiplist")]
+impl<T> Default for SkipList<T> {
+    fn default() -> Self {
+        Self::new()
     }
 }
 #[snippet("r3yohei_skiplist")]
 impl<T> Drop for SkipList<T> {
     fn drop(&mut self) {
-        let mut cur = self.head.next[0];
+        let mut cur = self.head;
         while !cur.is_null() {
             unsafe {
                 let next = (*cur).next[0];
@@ -193,42 +391,137 @@ impl<T: Debug> Debug for SkipList<T> {
     }
 }
 #[snippet("r3yohei_skiplist")]
+impl<T> FromIterator<T> for SkipList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        for x in iter {
+            list.push_back(x);
+        }
+        list
+    }
+}
+/// front, backはまだ返していない両端のノードで，len個残っている
+#[snippet("r3yohei_skiplist")]
 pub struct Iter<'a, T> {
-    _target: &'a SkipList<T>,
-    cur: *const Node<T>,
+    front: *const Node<T>,
+    back: *const Node<T>,
+    len: usize,
+    _marker: PhantomData<&'a T>,
 }
 #[snippet("r3yohei_skiplist")]
 pub struct IterMut<'a, T> {
-    _target: &'a SkipList<T>,
-    cur: *mut Node<T>,
+    front: *mut Node<T>,
+    back: *mut Node<T>,
+    len: usize,
+    _marker: PhantomData<&'a mut T>,
 }
 #[snippet("r3yohei_skiplist")]
 impl<'a, T> Iterator for Iter<'a, T> {
     type Item = &'a T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if unsafe { (*self.cur).next[0].is_null() } {
-            None
-        } else {
-            self.cur = unsafe { (*self.cur).next[0] };
-            Some(unsafe { &*(*self.cur).value })
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        let node = self.front;
+        unsafe {
+            self.front = (*node).next[0];
+            (*node).value.as_ref()
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
 }
 #[snippet("r3yohei_skiplist")]
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        let node = self.back;
+        unsafe {
+            self.back = (*node).prev;
+            (*node).value.as_ref()
+        }
+    }
+}
+#[snippet("r3yohei_skiplist")]
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+#[snippet("r3yohei_skiplist")]
 impl<'a, T> Iterator for IterMut<'a, T> {
     type Item = &'a mut T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if unsafe { (*self.cur).next[0].is_null() } {
-            None
-        } else {
-            self.cur = unsafe { (*self.cur).next[0] };
-            Some(unsafe { &mut *(*self.cur).value })
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        let node = self.front;
+        unsafe {
+            self.front = (*node).next[0];
+            (*node).value.as_mut()
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+#[snippet("r3yohei_skiplist")]
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        let node = self.back;
+        unsafe {
+            self.back = (*node).prev;
+            (*node).value.as_mut()
         }
     }
 }
 #[snippet("r3yohei_skiplist")]
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {}
+/// 要素を所有して返すイテレータ
+#[snippet("r3yohei_skiplist")]
+pub struct IntoIter<T> {
+    list: SkipList<T>,
+}
+#[snippet("r3yohei_skiplist")]
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.list.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.len(), Some(self.list.len()))
+    }
+}
+#[snippet("r3yohei_skiplist")]
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.list.pop_back()
+    }
+}
+#[snippet("r3yohei_skiplist")]
+impl<T> ExactSizeIterator for IntoIter<T> {}
+#[snippet("r3yohei_skiplist")]
+impl<T> IntoIterator for SkipList<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { list: self }
+    }
+}
+#[snippet("r3yohei_skiplist")]
 impl<'a, T> IntoIterator for &'a SkipList<T> {
     type Item = &'a T;
     type IntoIter = Iter<'a, T>;
@@ -250,23 +543,343 @@ impl<'a, T> IntoIterator for &'a mut SkipList<T> {
 impl<T> Index<usize> for SkipList<T> {
     type Output = T;
 
-    fn index(&self, mut index: usize) -> &Self::Output {
-        if index >= self.len {
-            panic!("index out of bounds");
+    fn index(&self, index: usize) -> &Self::Output {
+        self.get(index).expect("index out of bounds")
+    }
+}
+#[snippet("r3yohei_skiplist")]
+impl<T> IndexMut<usize> for SkipList<T> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        self.get_mut(index).expect("index out of bounds")
+    }
+}
+
+#[snippet("r3yohei_skiplist")]
+#[snippet(include = "r3yohei_list")]
+impl<T: Clone> List<T> for SkipList<T> {
+    fn size(&self) -> usize {
+        self.len
+    }
+    fn get(&self, i: usize) -> Option<T> {
+        self.get(i).cloned()
+    }
+    fn set(&mut self, i: usize, x: T) -> Option<T> {
+        self.get_mut(i).map(|y| std::mem::replace(y, x))
+    }
+    fn add(&mut self, i: usize, x: T) {
+        self.insert(i, x);
+    }
+    fn remove(&mut self, i: usize) -> Option<T> {
+        if i < self.len { Some(SkipList::remove(self, i)) } else { None }
+    }
+    fn visit(&self, f: &mut dyn FnMut(&T) -> bool) {
+        for x in self.iter() {
+            if !f(x) {
+                break;
+            }
         }
+    }
+    fn add_all(&mut self, i: usize, xs: Vec<T>) {
+        let mut tail = self.split_off(i);
+        for x in xs {
+            self.push_back(x);
+        }
+        self.append(&mut tail);
+    }
+    fn remove_range(&mut self, l: usize, r: usize) -> Vec<T> {
+        self.drain(l..r).collect()
+    }
+}
 
-        let mut cur = &self.head as *const Node<T>;
-        index += 1;
+/// スキップリストによる順序付き多重集合
+/// 値の順に並べたSkipListで，挿入･削除･k番目･順位がすべて期待O(logN)
+#[snippet("r3yohei_SkipMultiSet")]
+#[snippet(include = "r3yohei_skiplist")]
+#[derive(Debug, Default)]
+pub struct SkipMultiSet<T> {
+    list: SkipList<T>,
+}
+#[snippet("r3yohei_SkipMultiSet")]
+impl<T: Ord> SkipMultiSet<T> {
+    pub fn new() -> Self {
+        Self { list: SkipList::new() }
+    }
 
-        for l in (0..MAX_LEVEL).rev() {
-            unsafe {
-                while (*cur).skip[l] <= index {
-                    index -= (*cur).skip[l];
-                    cur = (*cur).next[l];
+    pub fn with_seed(seed: u64) -> Self {
+        Self { list: SkipList::with_seed(seed) }
+    }
+
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    /// xを追加する．同じ値があればその後ろに入る
+    pub fn insert(&mut self, x: T) {
+        let i = self.upper_bound(&x);
+        self.list.insert(i, x);
+    }
+
+    /// xがなければ追加してtrueを返す (集合として使う)
+    pub fn insert_unique(&mut self, x: T) -> bool {
+        if self.contains(&x) {
+            return false;
+        }
+        self.insert(x);
+        true
+    }
+
+    /// xを1つ削除する．なければfalse
+    pub fn remove(&mut self, x: &T) -> bool {
+        let i = self.lower_bound(x);
+        if self.list.get(i) == Some(x) {
+            self.list.remove(i);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// x以上の最初の要素の位置
+    pub fn lower_bound(&self, x: &T) -> usize {
+        self.list.partition_point(|v| v < x)
+    }
+
+    /// xより大きい最初の要素の位置
+    pub fn upper_bound(&self, x: &T) -> usize {
+        self.list.partition_point(|v| v <= x)
+    }
+
+    /// xより小さい要素の個数
+    pub fn rank(&self, x: &T) -> usize {
+        self.lower_bound(x)
+    }
+
+    /// k番目(0始まり)に小さい要素
+    pub fn kth(&self, k: usize) -> Option<&T> {
+        self.list.get(k)
+    }
+
+    pub fn count(&self, x: &T) -> usize {
+        self.upper_bound(x) - self.lower_bound(x)
+    }
+
+    pub fn contains(&self, x: &T) -> bool {
+        self.list.get(self.lower_bound(x)) == Some(x)
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.list.iter()
+    }
+}
+
+// 生成と破棄の回数を数える．リークや二重解放がないかを確かめる
+#[cfg(test)]
+struct DropCounter(std::rc::Rc<std::cell::Cell<usize>>);
+#[cfg(test)]
+impl Drop for DropCounter {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+// cargo +nightly miri test skiplist でエイリアスやリークを確かめられる
+#[test]
+fn test_skiplist() {
+    let mut rng = Xoshiro256::new(8192);
+    let mut list = SkipList::with_seed(1);
+    let mut naive = vec![];
+    let q = if cfg!(miri) { 300 } else { 10000 };
+    for _ in 0..q {
+        match rng.gen_usize(0, 4) {
+            0 | 1 => {
+                let i = rng.gen_usize(0, naive.len() + 1);
+                let x = rng.gen_i64(0, 100);
+                list.insert(i, x);
+                naive.insert(i, x);
+            }
+            2 => {
+                if !naive.is_empty() {
+                    let i = rng.gen_usize(0, naive.len());
+                    assert_eq!(list.remove(i), naive.remove(i));
+                }
+            }
+            _ => {
+                if !naive.is_empty() {
+                    let i = rng.gen_usize(0, naive.len());
+                    assert_eq!(list[i], naive[i]);
+                    list[i] += 1;
+                    naive[i] += 1;
                 }
             }
         }
+        assert_eq!(list.len(), naive.len());
+    }
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), naive);
+    for x in list.iter_mut() {
+        *x *= 2;
+    }
+    assert_eq!(list.get(naive.len()), None);
+    assert_eq!(list.pop_front(), naive.first().map(|x| x * 2));
+    assert_eq!(format!("{:?}", SkipList::from_iter(vec![1, 2, 3])), "[1, 2, 3]");
+}
 
-        unsafe { &*(*cur).value }
+#[test]
+fn test_skiplist_range() {
+    let mut rng = Xoshiro256::new(8192);
+    let mut list = SkipList::with_seed(1);
+    let mut naive = vec![];
+    let q = if cfg!(miri) { 200 } else { 5000 };
+    for t in 0..q {
+        let n = naive.len();
+        match rng.gen_usize(0, 6) {
+            0 | 1 => {
+                let i = rng.gen_usize(0, n + 1);
+                list.insert(i, t);
+                naive.insert(i, t);
+            }
+            2 => {
+                // [l, r) を切り取って位置pに貼り付ける
+                let l = rng.gen_usize(0, n + 1);
+                let r = rng.gen_usize(l, n + 1);
+                let mut tail = list.split_off(r);
+                let mut mid = list.split_off(l);
+                list.append(&mut tail);
+                assert!(tail.is_empty());
+                let p = rng.gen_usize(0, list.len() + 1);
+                let mut rest = list.split_off(p);
+                list.append(&mut mid);
+                list.append(&mut rest);
+                let cut = naive.drain(l..r).collect::<Vec<_>>();
+                naive.splice(p..p, cut);
+            }
+            3 => {
+                let l = rng.gen_usize(0, n + 1);
+                let r = rng.gen_usize(l, n + 1);
+                assert_eq!(list.drain(l..r).rev().collect::<Vec<_>>(), naive.drain(l..r).rev().collect::<Vec<_>>());
+            }
+            4 => {
+                let l = rng.gen_usize(0, n + 1);
+                let r = rng.gen_usize(l, n + 1);
+                for x in list.range_mut(l..r) {
+                    *x += 1;
+                }
+                for x in naive[l..r].iter_mut() {
+                    *x += 1;
+                }
+            }
+            _ => {
+                let l = rng.gen_usize(0, n + 1);
+                let r = rng.gen_usize(l, n + 1);
+                let mut it = list.range(l..r);
+                assert_eq!(it.len(), r - l);
+                // 両端から交互に進める
+                let mut front = vec![];
+                let mut back = vec![];
+                loop {
+                    match if rng.gen_bool(0.5) { it.next().map(|x| (true, x)) } else { it.next_back().map(|x| (false, x)) } {
+                        Some((true, &x)) => front.push(x),
+                        Some((false, &x)) => back.push(x),
+                        None => break,
+                    }
+                }
+                back.reverse();
+                front.extend(back);
+                assert_eq!(front, naive[l..r]);
+            }
+        }
+        assert_eq!(list.len(), naive.len());
+        assert_eq!(list.front(), naive.first());
+        assert_eq!(list.back(), naive.last());
+    }
+    assert_eq!(list.iter().rev().copied().collect::<Vec<_>>(), naive.iter().rev().copied().collect::<Vec<_>>());
+    assert_eq!(list.into_iter().collect::<Vec<_>>(), naive);
+}
+
+#[test]
+fn test_skip_multiset() {
+    let mut rng = Xoshiro256::new(8192);
+    let mut set = SkipMultiSet::with_seed(1);
+    let mut naive: Vec<i64> = vec![];
+    let q = if cfg!(miri) { 300 } else { 10000 };
+    for _ in 0..q {
+        let x = rng.gen_i64(0, 50);
+        match rng.gen_usize(0, 3) {
+            0 => {
+                set.insert(x);
+                let i = naive.partition_point(|&v| v <= x);
+                naive.insert(i, x);
+            }
+            1 => {
+                let removed = set.remove(&x);
+                match naive.iter().position(|&v| v == x) {
+                    Some(i) => {
+                        assert!(removed);
+                        naive.remove(i);
+                    }
+                    None => assert!(!removed),
+                }
+            }
+            _ => {
+                assert_eq!(set.lower_bound(&x), naive.partition_point(|&v| v < x));
+                assert_eq!(set.upper_bound(&x), naive.partition_point(|&v| v <= x));
+                assert_eq!(set.count(&x), naive.iter().filter(|&&v| v == x).count());
+                assert_eq!(set.contains(&x), naive.contains(&x));
+                let k = rng.gen_usize(0, naive.len() + 1);
+                assert_eq!(set.kth(k), naive.get(k));
+            }
+        }
+        assert_eq!(set.len(), naive.len());
+    }
+    assert_eq!(set.iter().copied().collect::<Vec<_>>(), naive);
+
+    let mut set = SkipMultiSet::new();
+    assert!(set.insert_unique(3));
+    assert!(!set.insert_unique(3));
+    set.insert(3);
+    assert_eq!(set.count(&3), 2);
+    assert_eq!(set.rank(&3), 0);
+    assert_eq!(set.rank(&4), 2);
+}
+
+#[test]
+fn test_skiplist_drop() {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    let dropped = Rc::new(Cell::new(0));
+    {
+        let mut list = SkipList::new();
+        for i in 0..100 {
+            list.insert(i / 2, DropCounter(dropped.clone()));
+        }
+        // 取り出した要素はここで破棄される
+        for _ in 0..30 {
+            list.remove(10);
+        }
+        assert_eq!(dropped.get(), 30);
+        list.pop_back();
+        list.pop_front();
+        assert_eq!(dropped.get(), 32);
+    }
+    // 残りはリストと一緒に破棄される
+    assert_eq!(dropped.get(), 100);
+
+    // 途中で止めたdrainやinto_iterも残りを破棄する
+    let dropped = Rc::new(Cell::new(0));
+    {
+        let mut list = (0..100).map(|_| DropCounter(dropped.clone())).collect::<SkipList<_>>();
+        let mut other = list.split_off(40);
+        assert_eq!(dropped.get(), 0);
+        drop(list.drain(10..20).next());
+        assert_eq!(dropped.get(), 10);
+        list.append(&mut other);
+        let mut it = list.into_iter();
+        it.next_back();
+        assert_eq!(dropped.get(), 11);
     }
+    assert_eq!(dropped.get(), 100);
 }
@@ -0,0 +1,137 @@
+use cargo_snippet::snippet;
+use crate::data_structure::segtree::Monoid;
+
+/// スパーステーブル
+/// 冪等な演算(min, max, gcdなど)の区間の積を，前計算O(NlogN)，クエリO(1)で求める
+/// 重なる2つの区間の積をとるので，冪等でない演算(和など)には使えない
+#[snippet("r3yohei_SparseTable")]
+#[snippet(include = "r3yohei_Monoid")]
+pub struct SparseTable<M: Monoid> {
+    // table[k][i]: [i, i + 2^k) の積
+    table: Vec<Vec<M::S>>,
+}
+#[snippet("r3yohei_SparseTable")]
+impl<M: Monoid> SparseTable<M> {
+    pub fn new(a: &[M::S]) -> Self {
+        let mut table = vec![a.to_vec()];
+        let mut k = 1;
+        while (1 << k) <= a.len() {
+            let prev = &table[k - 1];
+            let half = 1 << (k - 1);
+            let next = (0..=a.len() - (1 << k))
+                .map(|i| M::binary_operation(&prev[i], &prev[i + half]))
+                .collect();
+            table.push(next);
+            k += 1;
+        }
+        Self { table }
+    }
+
+    pub fn len(&self) -> usize {
+        self.table[0].len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.table[0].is_empty()
+    }
+
+    /// [l, r) の積
+    pub fn prod(&self, l: usize, r: usize) -> M::S {
+        assert!(l <= r && r <= self.len());
+        if l == r {
+            return M::identity();
+        }
+        let k = (r - l).ilog2() as usize;
+        M::binary_operation(&self.table[k][l], &self.table[k][r - (1 << k)])
+    }
+}
+
+/// 二重スパーステーブル(disjoint sparse table)
+/// 結合則を満たす任意の演算の区間の積を，前計算O(NlogN)，クエリO(1)で求める
+#[snippet("r3yohei_DisjointSparseTable")]
+#[snippet(include = "r3yohei_Monoid")]
+pub struct DisjointSparseTable<M: Monoid> {
+    // table[k][i]: 長さ2^(k+1)のブロックを半分に分け，iから真ん中までの積
+    // (iが左半分なら[i, mid)，右半分なら[mid, i])
+    table: Vec<Vec<M::S>>,
+}
+#[snippet("r3yohei_DisjointSparseTable")]
+impl<M: Monoid> DisjointSparseTable<M> {
+    pub fn new(a: &[M::S]) -> Self {
+        let n = a.len();
+        let mut table = vec![];
+        let mut k = 0;
+        while (1 << k) < n || k == 0 {
+            let half = 1 << k;
+            let mut row = a.to_vec();
+            let mut mid = half;
+            while mid < n {
+                for i in (mid - half..mid - 1).rev() {
+                    row[i] = M::binary_operation(&a[i], &row[i + 1]);
+                }
+                for i in mid + 1..(mid + half).min(n) {
+                    row[i] = M::binary_operation(&row[i - 1], &a[i]);
+                }
+                mid += 2 * half;
+            }
+            table.push(row);
+            k += 1;
+        }
+        Self { table }
+    }
+
+    pub fn len(&self) -> usize {
+        self.table[0].len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.table[0].is_empty()
+    }
+
+    /// [l, r) の積
+    pub fn prod(&self, l: usize, r: usize) -> M::S {
+        assert!(l <= r && r <= self.len());
+        if l == r {
+            return M::identity();
+        }
+        let r = r - 1;
+        if l == r {
+            return self.table[0][l].clone();
+        }
+        // lとrが初めて別の半分に分かれる段
+        let k = (l ^ r).ilog2() as usize;
+        M::binary_operation(&self.table[k][l], &self.table[k][r])
+    }
+}
+
+#[test]
+fn test_sparse_table() {
+    use crate::data_structure::segtree::{Additive, Affine, Gcd, Max, Min};
+    use crate::rand::xoshiro256::Xoshiro256;
+    use crate::util::modint::ModInt;
+
+    let mut rng = Xoshiro256::new(8192);
+    for n in [0, 1, 2, 3, 8, 13, 64, 100] {
+        let a = (0..n).map(|_| rng.gen_i64(-100, 100)).collect::<Vec<_>>();
+        let g = (0..n).map(|_| 6 * rng.gen_usize(0, 20) as u64).collect::<Vec<_>>();
+        let fs = (0..n).map(|_| (ModInt::new(rng.gen_usize(0, 100)), ModInt::new(rng.gen_usize(0, 100)))).collect::<Vec<_>>();
+        let min = SparseTable::<Min<i64>>::new(&a);
+        let max = SparseTable::<Max<i64>>::new(&a);
+        let gcd = SparseTable::<Gcd>::new(&g);
+        let sum = DisjointSparseTable::<Additive<i64>>::new(&a);
+        let affine = DisjointSparseTable::<Affine>::new(&fs);
+        assert_eq!(min.len(), n);
+        for l in 0..=n {
+            for r in l..=n {
+                assert_eq!(min.prod(l, r), a[l..r].iter().copied().min().unwrap_or(i64::MAX));
+                assert_eq!(max.prod(l, r), a[l..r].iter().copied().max().unwrap_or(i64::MIN));
+                assert_eq!(gcd.prod(l, r), g[l..r].iter().fold(0, |acc, x| Gcd::binary_operation(&acc, x)));
+                assert_eq!(sum.prod(l, r), a[l..r].iter().sum::<i64>());
+                let (c, d) = affine.prod(l, r);
+                let (e, f) = fs[l..r].iter().fold(Affine::identity(), |acc, f| Affine::binary_operation(&acc, f));
+                assert_eq!((c.value(), d.value()), (e.value(), f.value()));
+            }
+        }
+    }
+    assert_eq!(Gcd::binary_operation(&12, &18), 6);
+}
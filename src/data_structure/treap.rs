@@ -0,0 +1,941 @@
+use cargo_snippet::snippet;
+use crate::data_structure::lazy_segtree::MapMonoid;
+use crate::data_structure::list::List;
+use crate::data_structure::segtree::Monoid;
+use crate::rand::xoshiro256::Xoshiro256;
+
+/// 暗黙のキーのtreapの節点．子がなければ!0
+/// lazyとrevは子に伝える前の作用で，自分のval, prod, rev_prodには反映済み
+#[snippet("r3yohei_ImplicitTreap")]
+#[derive(Clone, Debug)]
+struct ImplicitNode<S, F> {
+    val: S,
+    // 部分木を前から順にかけたものと，後ろから順にかけたもの
+    prod: S,
+    rev_prod: S,
+    lazy: F,
+    rev: bool,
+    size: usize,
+    pri: u64,
+    left: usize,
+    right: usize,
+}
+
+/// 暗黙のキーのtreap (位置で分割･結合する平衡二分木)
+/// 位置を指定した挿入･削除，区間の積，区間への作用，区間の反転が期待O(logN)
+/// 節点は配列に確保し，優先度はシード付きのXoshiro256で決めるので，同じシードなら同じ形になる
+/// 1つの配列に複数の木を持てる．木は根の番号(空なら!0)で表し，split, mergeで節点を複製せずに分割･結合する
+/// insertなどの位置を指定する操作はrootの木に対して行う
+#[snippet("r3yohei_ImplicitTreap")]
+#[snippet(include = "r3yohei_MapMonoid")]
+#[snippet(include = "r3yohei_Xoshiro256")]
+pub struct ImplicitTreap<F: MapMonoid> {
+    nodes: Vec<ImplicitNode<<F::M as Monoid>::S, F::F>>,
+    free: Vec<usize>,
+    root: usize,
+    rng: Xoshiro256,
+}
+#[snippet("r3yohei_ImplicitTreap")]
+impl<F: MapMonoid> ImplicitTreap<F> {
+    pub fn new() -> Self {
+        Self::with_seed(0)
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self { nodes: vec![], free: vec![], root: !0, rng: Xoshiro256::new(seed) }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len_of(self.root)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root == !0
+    }
+
+    /// 操作の対象になっている木の根
+    pub fn root(&self) -> usize {
+        self.root
+    }
+
+    /// 操作の対象をrootの木にする
+    pub fn set_root(&mut self, root: usize) {
+        self.root = root;
+    }
+
+    fn alloc(&mut self, x: <F::M as Monoid>::S) -> usize {
+        let node = ImplicitNode {
+            prod: x.clone(),
+            rev_prod: x.clone(),
+            val: x,
+            lazy: F::identity_map(),
+            rev: false,
+            size: 1,
+            pri: self.rng.next_u64(),
+            left: !0,
+            right: !0,
+        };
+        if let Some(v) = self.free.pop() {
+            self.nodes[v] = node;
+            v
+        } else {
+            self.nodes.push(node);
+            self.nodes.len() - 1
+        }
+    }
+
+    /// 根がvの木の大きさ
+    pub fn len_of(&self, v: usize) -> usize {
+        if v == !0 { 0 } else { self.nodes[v].size }
+    }
+
+    /// 根がvの木の全体の積
+    pub fn prod_of(&self, v: usize) -> <F::M as Monoid>::S {
+        if v == !0 { F::M::identity() } else { self.nodes[v].prod.clone() }
+    }
+
+    fn rev_prod_of(&self, v: usize) -> <F::M as Monoid>::S {
+        if v == !0 { F::M::identity() } else { self.nodes[v].rev_prod.clone() }
+    }
+
+    fn update(&mut self, v: usize) {
+        let (l, r) = (self.nodes[v].left, self.nodes[v].right);
+        let val = &self.nodes[v].val;
+        let prod = F::M::binary_operation(&F::M::binary_operation(&self.prod_of(l), val), &self.prod_of(r));
+        let rev_prod = F::M::binary_operation(&F::M::binary_operation(&self.rev_prod_of(r), val), &self.rev_prod_of(l));
+        self.nodes[v].size = self.len_of(l) + self.len_of(r) + 1;
+        self.nodes[v].prod = prod;
+        self.nodes[v].rev_prod = rev_prod;
+    }
+
+    fn all_apply(&mut self, v: usize, f: &F::F) {
+        if v == !0 {
+            return;
+        }
+        let node = &mut self.nodes[v];
+        node.val = F::mapping(f, &node.val);
+        node.prod = F::mapping(f, &node.prod);
+        node.rev_prod = F::mapping(f, &node.rev_prod);
+        node.lazy = F::composition(f, &node.lazy);
+    }
+
+    fn toggle(&mut self, v: usize) {
+        if v == !0 {
+            return;
+        }
+        let node = &mut self.nodes[v];
+        std::mem::swap(&mut node.left, &mut node.right);
+        std::mem::swap(&mut node.prod, &mut node.rev_prod);
+        node.rev ^= true;
+    }
+
+    // 遅延させていた作用を子に伝える
+    fn push(&mut self, v: usize) {
+        let (l, r) = (self.nodes[v].left, self.nodes[v].right);
+        if self.nodes[v].rev {
+            self.toggle(l);
+            self.toggle(r);
+            self.nodes[v].rev = false;
+        }
+        let f = std::mem::replace(&mut self.nodes[v].lazy, F::identity_map());
+        self.all_apply(l, &f);
+        self.all_apply(r, &f);
+    }
+
+    /// 根がaの木の後ろに根がbの木をつなぎ，新しい根を返す 期待O(logN)
+    /// aとbは別の木の根であること
+    pub fn merge(&mut self, a: usize, b: usize) -> usize {
+        if a == !0 {
+            return b;
+        }
+        if b == !0 {
+            return a;
+        }
+        if self.nodes[a].pri > self.nodes[b].pri {
+            self.push(a);
+            let r = self.merge(self.nodes[a].right, b);
+            self.nodes[a].right = r;
+            self.update(a);
+            a
+        } else {
+            self.push(b);
+            let l = self.merge(a, self.nodes[b].left);
+            self.nodes[b].left = l;
+            self.update(b);
+            b
+        }
+    }
+
+    /// 根がvの木を前からk個と残りに分け，それぞれの根を返す 期待O(logN)
+    pub fn split(&mut self, v: usize, k: usize) -> (usize, usize) {
+        if v == !0 {
+            return (!0, !0);
+        }
+        self.push(v);
+        let l = self.nodes[v].left;
+        if k <= self.len_of(l) {
+            let (a, b) = self.split(l, k);
+            self.nodes[v].left = b;
+            self.update(v);
+            (a, v)
+        } else {
+            let (a, b) = self.split(self.nodes[v].right, k - self.len_of(l) - 1);
+            self.nodes[v].right = a;
+            self.update(v);
+            (v, b)
+        }
+    }
+
+    // 木を[0, l), [l, r), [r, N)に分け，真ん中にfを使ってから戻す
+    fn with_range<R>(&mut self, l: usize, r: usize, f: impl FnOnce(&mut Self, usize) -> R) -> R {
+        assert!(l <= r && r <= self.len(), "range out of bounds");
+        let (a, bc) = self.split(self.root, l);
+        let (b, c) = self.split(bc, r - l);
+        let res = f(self, b);
+        let ab = self.merge(a, b);
+        self.root = self.merge(ab, c);
+        res
+    }
+
+    // 部分木vを前から順に見る．親から伝わっていない作用も反映した値をfに渡す
+    fn walk(&self, v: usize, f: &mut dyn FnMut(&<F::M as Monoid>::S) -> bool) {
+        // (節点, 上から来た作用, 上から来た反転, 子を積んだか)
+        let mut stack = vec![(v, F::identity_map(), false, false)];
+        while let Some((v, g, rev, expanded)) = stack.pop() {
+            if v == !0 {
+                continue;
+            }
+            let node = &self.nodes[v];
+            if expanded {
+                if !f(&F::mapping(&g, &node.val)) {
+                    return;
+                }
+                continue;
+            }
+            let (l, r) = if rev { (node.right, node.left) } else { (node.left, node.right) };
+            let cg = F::composition(&g, &node.lazy);
+            let crev = rev ^ node.rev;
+            stack.push((r, cg.clone(), crev, false));
+            stack.push((v, g, rev, true));
+            stack.push((l, cg, crev, false));
+        }
+    }
+
+    /// i番目にxを入れる
+    pub fn insert(&mut self, i: usize, x: <F::M as Monoid>::S) {
+        assert!(i <= self.len(), "index out of bounds");
+        let v = self.alloc(x);
+        let (a, b) = self.split(self.root, i);
+        let av = self.merge(a, v);
+        self.root = self.merge(av, b);
+    }
+
+    /// i番目を取り除いて返す
+    pub fn remove(&mut self, i: usize) -> <F::M as Monoid>::S {
+        assert!(i < self.len(), "index out of bounds");
+        let (a, bc) = self.split(self.root, i);
+        let (b, c) = self.split(bc, 1);
+        self.root = self.merge(a, c);
+        self.free.push(b);
+        std::mem::replace(&mut self.nodes[b].val, F::M::identity())
+    }
+
+    pub fn get(&self, i: usize) -> Option<<F::M as Monoid>::S> {
+        if i >= self.len() {
+            return None;
+        }
+        // 作用と反転を伝えながら下りる
+        let (mut v, mut i) = (self.root, i);
+        let (mut g, mut rev) = (F::identity_map(), false);
+        loop {
+            let node = &self.nodes[v];
+            let (l, r) = if rev { (node.right, node.left) } else { (node.left, node.right) };
+            if i == self.len_of(l) {
+                return Some(F::mapping(&g, &node.val));
+            }
+            g = F::composition(&g, &node.lazy);
+            rev ^= node.rev;
+            if i < self.len_of(l) {
+                v = l;
+            } else {
+                i -= self.len_of(l) + 1;
+                v = r;
+            }
+        }
+    }
+
+    /// i番目をxにする
+    pub fn set(&mut self, i: usize, x: <F::M as Monoid>::S) {
+        assert!(i < self.len(), "index out of bounds");
+        self.with_range(i, i + 1, |t, v| {
+            t.nodes[v].val = x;
+            t.update(v);
+        });
+    }
+
+    /// [l, r) の積
+    pub fn prod(&mut self, l: usize, r: usize) -> <F::M as Monoid>::S {
+        self.with_range(l, r, |t, v| t.prod_of(v))
+    }
+
+    pub fn all_prod(&self) -> <F::M as Monoid>::S {
+        self.prod_of(self.root)
+    }
+
+    /// [l, r) にfを作用させる
+    pub fn apply(&mut self, l: usize, r: usize, f: F::F) {
+        self.with_range(l, r, |t, v| t.all_apply(v, &f));
+    }
+
+    /// [l, r) を反転する
+    pub fn reverse(&mut self, l: usize, r: usize) {
+        self.with_range(l, r, |t, v| t.toggle(v));
+    }
+
+    /// [l, r) を取り除いて返す
+    pub fn drain(&mut self, l: usize, r: usize) -> Vec<<F::M as Monoid>::S> {
+        assert!(l <= r && r <= self.len(), "range out of bounds");
+        let (a, bc) = self.split(self.root, l);
+        let (b, c) = self.split(bc, r - l);
+        self.root = self.merge(a, c);
+        let mut res = Vec::with_capacity(r - l);
+        self.walk(b, &mut |x| {
+            res.push(x.clone());
+            true
+        });
+        self.free_tree(b);
+        res
+    }
+
+    /// 根がvの木の節点を全て捨て，使い回せるようにする
+    pub fn free_tree(&mut self, v: usize) {
+        let mut stack = vec![v];
+        while let Some(v) = stack.pop() {
+            if v != !0 {
+                stack.push(self.nodes[v].left);
+                stack.push(self.nodes[v].right);
+                self.nodes[v].val = F::M::identity();
+                self.free.push(v);
+            }
+        }
+    }
+
+    /// xsを順に並べた木を作り，その根を返す．rootの木とは別の木になる O(N)
+    pub fn build(&mut self, xs: Vec<<F::M as Monoid>::S>) -> usize {
+        // 優先度が単調減少になるように右端の節点を積んでいく
+        let mut stack: Vec<usize> = vec![];
+        for x in xs {
+            let v = self.alloc(x);
+            let mut last = !0;
+            while let Some(&top) = stack.last() {
+                if self.nodes[top].pri > self.nodes[v].pri {
+                    break;
+                }
+                stack.pop();
+                self.update(top);
+                last = top;
+            }
+            self.nodes[v].left = last;
+            if let Some(&top) = stack.last() {
+                self.nodes[top].right = v;
+            }
+            stack.push(v);
+        }
+        let mut root = !0;
+        while let Some(v) = stack.pop() {
+            self.update(v);
+            root = v;
+        }
+        root
+    }
+
+    /// i番目からxsを順に入れる
+    pub fn insert_many(&mut self, i: usize, xs: Vec<<F::M as Monoid>::S>) {
+        assert!(i <= self.len(), "index out of bounds");
+        let mid = self.build(xs);
+        let (a, b) = self.split(self.root, i);
+        let am = self.merge(a, mid);
+        self.root = self.merge(am, b);
+    }
+
+    pub fn to_vec(&self) -> Vec<<F::M as Monoid>::S> {
+        self.to_vec_of(self.root)
+    }
+
+    /// 根がvの木を前から並べたもの
+    pub fn to_vec_of(&self, v: usize) -> Vec<<F::M as Monoid>::S> {
+        let mut res = Vec::with_capacity(self.len_of(v));
+        self.walk(v, &mut |x| {
+            res.push(x.clone());
+            true
+        });
+        res
+    }
+
+    /// rootの木を空にする．他の木はそのまま残る
+    pub fn clear(&mut self) {
+        self.free_tree(self.root);
+        self.root = !0;
+    }
+}
+#[snippet("r3yohei_ImplicitTreap")]
+impl<F: MapMonoid> Default for ImplicitTreap<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+#[snippet("r3yohei_ImplicitTreap")]
+impl<F: MapMonoid> From<Vec<<F::M as Monoid>::S>> for ImplicitTreap<F> {
+    fn from(v: Vec<<F::M as Monoid>::S>) -> Self {
+        let mut treap = Self::new();
+        treap.insert_many(0, v);
+        treap
+    }
+}
+#[snippet("r3yohei_ImplicitTreap")]
+#[snippet(include = "r3yohei_list")]
+impl<F: MapMonoid> List<<F::M as Monoid>::S> for ImplicitTreap<F> {
+    fn size(&self) -> usize {
+        self.len()
+    }
+    fn get(&self, i: usize) -> Option<<F::M as Monoid>::S> {
+        ImplicitTreap::get(self, i)
+    }
+    fn set(&mut self, i: usize, x: <F::M as Monoid>::S) -> Option<<F::M as Monoid>::S> {
+        let old = ImplicitTreap::get(self, i)?;
+        ImplicitTreap::set(self, i, x);
+        Some(old)
+    }
+    fn add(&mut self, i: usize, x: <F::M as Monoid>::S) {
+        self.insert(i, x);
+    }
+    fn remove(&mut self, i: usize) -> Option<<F::M as Monoid>::S> {
+        if i < self.len() { Some(ImplicitTreap::remove(self, i)) } else { None }
+    }
+    fn visit(&self, f: &mut dyn FnMut(&<F::M as Monoid>::S) -> bool) {
+        self.walk(self.root, f);
+    }
+    fn add_all(&mut self, i: usize, xs: Vec<<F::M as Monoid>::S>) {
+        self.insert_many(i, xs);
+    }
+    fn remove_range(&mut self, l: usize, r: usize) -> Vec<<F::M as Monoid>::S> {
+        self.drain(l, r)
+    }
+    fn clear(&mut self) {
+        ImplicitTreap::clear(self);
+    }
+}
+
+/// キーで並べるtreapの節点
+#[snippet("r3yohei_TreapMultiSet")]
+#[derive(Clone, Debug)]
+struct KeyNode<K> {
+    key: K,
+    size: usize,
+    pri: u64,
+    left: usize,
+    right: usize,
+}
+
+/// treapによる順序付き多重集合
+/// キーで分割･結合し，挿入･削除･k番目･順位がすべて期待O(logN)
+/// ImplicitTreapと同じく1つの配列に複数の木を持て，split_by_key, mergeで節点を複製せずに分割･結合する
+#[snippet("r3yohei_TreapMultiSet")]
+#[snippet(include = "r3yohei_Xoshiro256")]
+pub struct TreapMultiSet<K> {
+    nodes: Vec<KeyNode<K>>,
+    free: Vec<usize>,
+    root: usize,
+    rng: Xoshiro256,
+}
+#[snippet("r3yohei_TreapMultiSet")]
+impl<K: Ord> TreapMultiSet<K> {
+    pub fn new() -> Self {
+        Self::with_seed(0)
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self { nodes: vec![], free: vec![], root: !0, rng: Xoshiro256::new(seed) }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len_of(self.root)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root == !0
+    }
+
+    /// 操作の対象になっている木の根
+    pub fn root(&self) -> usize {
+        self.root
+    }
+
+    /// 操作の対象をrootの木にする
+    pub fn set_root(&mut self, root: usize) {
+        self.root = root;
+    }
+
+    /// 根がvの木の大きさ
+    pub fn len_of(&self, v: usize) -> usize {
+        if v == !0 { 0 } else { self.nodes[v].size }
+    }
+
+    fn update(&mut self, v: usize) {
+        self.nodes[v].size = self.len_of(self.nodes[v].left) + self.len_of(self.nodes[v].right) + 1;
+    }
+
+    /// 根がaの木の後ろに根がbの木をつなぎ，新しい根を返す 期待O(logN)
+    /// aのキーは全てbのキー以下であること
+    pub fn merge(&mut self, a: usize, b: usize) -> usize {
+        debug_assert!(self.max_of(a).zip(self.min_of(b)).map_or(true, |(x, y)| x <= y));
+        self.merge_rec(a, b)
+    }
+
+    fn merge_rec(&mut self, a: usize, b: usize) -> usize {
+        if a == !0 {
+            return b;
+        }
+        if b == !0 {
+            return a;
+        }
+        if self.nodes[a].pri > self.nodes[b].pri {
+            let r = self.merge_rec(self.nodes[a].right, b);
+            self.nodes[a].right = r;
+            self.update(a);
+            a
+        } else {
+            let l = self.merge_rec(a, self.nodes[b].left);
+            self.nodes[b].left = l;
+            self.update(b);
+            b
+        }
+    }
+
+    /// 根がvの木をキーがxより小さいものとx以上のものに分け，それぞれの根を返す 期待O(logN)
+    pub fn split_by_key(&mut self, v: usize, x: &K) -> (usize, usize) {
+        self.split(v, x, false)
+    }
+
+    // キーがxより小さい(inclusiveならx以下の)ものと残りに分ける
+    fn split(&mut self, v: usize, x: &K, inclusive: bool) -> (usize, usize) {
+        if v == !0 {
+            return (!0, !0);
+        }
+        let go_right = if inclusive { self.nodes[v].key <= *x } else { self.nodes[v].key < *x };
+        if go_right {
+            let (a, b) = self.split(self.nodes[v].right, x, inclusive);
+            self.nodes[v].right = a;
+            self.update(v);
+            (v, b)
+        } else {
+            let (a, b) = self.split(self.nodes[v].left, x, inclusive);
+            self.nodes[v].left = b;
+            self.update(v);
+            (a, v)
+        }
+    }
+
+    /// xを追加する．同じ値があればその後ろに入る
+    pub fn insert(&mut self, x: K) {
+        let (a, b) = self.split(self.root, &x, true);
+        let node = KeyNode { key: x, size: 1, pri: self.rng.next_u64(), left: !0, right: !0 };
+        let v = if let Some(v) = self.free.pop() {
+            self.nodes[v] = node;
+            v
+        } else {
+            self.nodes.push(node);
+            self.nodes.len() - 1
+        };
+        let av = self.merge(a, v);
+        self.root = self.merge(av, b);
+    }
+
+    /// xがなければ追加してtrueを返す (集合として使う)
+    pub fn insert_unique(&mut self, x: K) -> bool {
+        if self.contains(&x) {
+            return false;
+        }
+        self.insert(x);
+        true
+    }
+
+    /// xを1つ削除する．なければfalse
+    pub fn remove(&mut self, x: &K) -> bool {
+        let (a, bc) = self.split(self.root, x, false);
+        let (b, c) = self.split(bc, x, true);
+        // bはキーがxのものだけ．根を1つ取り除く
+        let found = b != !0;
+        let b = if found {
+            self.free.push(b);
+            self.merge(self.nodes[b].left, self.nodes[b].right)
+        } else {
+            b
+        };
+        let ab = self.merge(a, b);
+        self.root = self.merge(ab, c);
+        found
+    }
+
+    // キーがxより小さい(inclusiveならx以下の)ものの個数
+    fn count_less(&self, x: &K, inclusive: bool) -> usize {
+        let (mut v, mut cnt) = (self.root, 0);
+        while v != !0 {
+            let node = &self.nodes[v];
+            let go_right = if inclusive { node.key <= *x } else { node.key < *x };
+            if go_right {
+                cnt += self.len_of(node.left) + 1;
+                v = node.right;
+            } else {
+                v = node.left;
+            }
+        }
+        cnt
+    }
+
+    /// x以上の最初の要素の位置
+    pub fn lower_bound(&self, x: &K) -> usize {
+        self.count_less(x, false)
+    }
+
+    /// xより大きい最初の要素の位置
+    pub fn upper_bound(&self, x: &K) -> usize {
+        self.count_less(x, true)
+    }
+
+    /// xより小さい要素の個数
+    pub fn rank(&self, x: &K) -> usize {
+        self.lower_bound(x)
+    }
+
+    /// k番目(0始まり)に小さい要素
+    pub fn kth(&self, mut k: usize) -> Option<&K> {
+        if k >= self.len() {
+            return None;
+        }
+        let mut v = self.root;
+        loop {
+            let node = &self.nodes[v];
+            let ls = self.len_of(node.left);
+            if k == ls {
+                return Some(&node.key);
+            }
+            if k < ls {
+                v = node.left;
+            } else {
+                k -= ls + 1;
+                v = node.right;
+            }
+        }
+    }
+
+    pub fn count(&self, x: &K) -> usize {
+        self.upper_bound(x) - self.lower_bound(x)
+    }
+
+    pub fn contains(&self, x: &K) -> bool {
+        self.count(x) > 0
+    }
+
+    /// 根がvの木の節点を全て捨て，使い回せるようにする
+    pub fn free_tree(&mut self, v: usize) {
+        let mut stack = vec![v];
+        while let Some(v) = stack.pop() {
+            if v != !0 {
+                stack.push(self.nodes[v].left);
+                stack.push(self.nodes[v].right);
+                self.free.push(v);
+            }
+        }
+    }
+
+    /// 根がvの木の最小のキー
+    pub fn min_of(&self, mut v: usize) -> Option<&K> {
+        if v == !0 {
+            return None;
+        }
+        while self.nodes[v].left != !0 {
+            v = self.nodes[v].left;
+        }
+        Some(&self.nodes[v].key)
+    }
+
+    /// 根がvの木の最大のキー
+    pub fn max_of(&self, mut v: usize) -> Option<&K> {
+        if v == !0 {
+            return None;
+        }
+        while self.nodes[v].right != !0 {
+            v = self.nodes[v].right;
+        }
+        Some(&self.nodes[v].key)
+    }
+
+    /// 小さい順に見る
+    pub fn iter(&self) -> TreapIter<'_, K> {
+        self.iter_of(self.root)
+    }
+
+    /// 根がvの木を小さい順に見る
+    pub fn iter_of(&self, v: usize) -> TreapIter<'_, K> {
+        let mut iter = TreapIter { set: self, stack: vec![] };
+        iter.push_left(v);
+        iter
+    }
+}
+#[snippet("r3yohei_TreapMultiSet")]
+impl<K: Ord> Default for TreapMultiSet<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[snippet("r3yohei_TreapMultiSet")]
+pub struct TreapIter<'a, K> {
+    set: &'a TreapMultiSet<K>,
+    // 次に見る節点と，その祖先のうちまだ見ていないもの
+    stack: Vec<usize>,
+}
+#[snippet("r3yohei_TreapMultiSet")]
+impl<K> TreapIter<'_, K> {
+    fn push_left(&mut self, mut v: usize) {
+        while v != !0 {
+            self.stack.push(v);
+            v = self.set.nodes[v].left;
+        }
+    }
+}
+#[snippet("r3yohei_TreapMultiSet")]
+impl<'a, K> Iterator for TreapIter<'a, K> {
+    type Item = &'a K;
+    fn next(&mut self) -> Option<&'a K> {
+        let v = self.stack.pop()?;
+        let set: &'a TreapMultiSet<K> = self.set;
+        self.push_left(set.nodes[v].right);
+        Some(&set.nodes[v].key)
+    }
+}
+
+// 反転を確かめるための非可換なモノイド．作用はない
+#[cfg(test)]
+struct AffineNoMap;
+#[cfg(test)]
+impl MapMonoid for AffineNoMap {
+    type M = crate::data_structure::segtree::Affine;
+    type F = ();
+    fn identity_map() {}
+    fn mapping(_: &(), x: &<Self::M as Monoid>::S) -> <Self::M as Monoid>::S {
+        *x
+    }
+    fn composition(_: &(), _: &()) {}
+}
+
+#[test]
+fn test_implicit_treap() {
+    use crate::data_structure::lazy_segtree::{RangeAddRangeMin, RangeAffineRangeSum};
+    use crate::util::modint::ModInt;
+
+    let mut rng = Xoshiro256::new(8192);
+    let mut treap = ImplicitTreap::<RangeAddRangeMin>::with_seed(1);
+    let mut naive: Vec<i64> = vec![];
+    let mut affine = ImplicitTreap::<RangeAffineRangeSum>::new();
+    let mut naive_affine: Vec<ModInt> = vec![];
+    let q = if cfg!(miri) { 300 } else { 3000 };
+    for _ in 0..q {
+        let n = naive.len();
+        let l = rng.gen_usize(0, n + 1);
+        let r = rng.gen_usize(l, n + 1);
+        match rng.gen_usize(0, 6) {
+            0 | 1 => {
+                let x = rng.gen_i64(-50, 50);
+                treap.insert(l, x);
+                naive.insert(l, x);
+                let y = ModInt::new(rng.gen_usize(0, 100));
+                affine.insert(l, (y, 1));
+                naive_affine.insert(l, y);
+            }
+            2 => {
+                if l < n {
+                    assert_eq!(treap.remove(l), naive.remove(l));
+                    assert_eq!(affine.remove(l).0.value(), naive_affine.remove(l).value());
+                }
+            }
+            3 => {
+                let x = rng.gen_i64(-5, 5);
+                treap.apply(l, r, x);
+                naive[l..r].iter_mut().for_each(|v| *v += x);
+                let (a, b) = (ModInt::new(rng.gen_usize(0, 10)), ModInt::new(rng.gen_usize(0, 10)));
+                affine.apply(l, r, (a, b));
+                naive_affine[l..r].iter_mut().for_each(|v| *v = a * *v + b);
+            }
+            4 => {
+                treap.reverse(l, r);
+                naive[l..r].reverse();
+                affine.reverse(l, r);
+                naive_affine[l..r].reverse();
+            }
+            _ => {
+                assert_eq!(treap.prod(l, r), naive[l..r].iter().copied().min().unwrap_or(i64::MAX));
+                let sum = naive_affine[l..r].iter().fold(ModInt::zero(), |acc, &x| acc + x);
+                assert_eq!(affine.prod(l, r).0.value(), sum.value());
+                if l < n {
+                    assert_eq!(treap.get(l), Some(naive[l]));
+                }
+            }
+        }
+        assert_eq!(treap.len(), naive.len());
+    }
+    assert_eq!(treap.to_vec(), naive);
+    assert_eq!(treap.all_prod(), naive.iter().copied().min().unwrap_or(i64::MAX));
+
+    // 非可換な積でも反転した区間の積が正しい
+    let fs = (0..30).map(|_| (ModInt::new(rng.gen_usize(1, 100)), ModInt::new(rng.gen_usize(0, 100)))).collect::<Vec<_>>();
+    let mut treap = ImplicitTreap::<AffineNoMap>::from(fs.clone());
+    let mut naive = fs;
+    for _ in 0..200 {
+        let l = rng.gen_usize(0, 31);
+        let r = rng.gen_usize(l, 31);
+        if rng.gen_bool(0.5) {
+            treap.reverse(l, r);
+            naive[l..r].reverse();
+        } else {
+            let (a, b) = treap.prod(l, r);
+            let (c, d) = naive[l..r].iter().fold((ModInt::one(), ModInt::zero()), |acc, &(a, b)| (acc.0 * a, acc.1 * a + b));
+            assert_eq!((a.value(), b.value()), (c.value(), d.value()));
+        }
+    }
+}
+
+#[test]
+fn test_treap_multiset() {
+    let mut rng = Xoshiro256::new(8192);
+    let mut set = TreapMultiSet::new();
+    let mut naive: Vec<i64> = vec![];
+    let q = if cfg!(miri) { 300 } else { 3000 };
+    for _ in 0..q {
+        let x = rng.gen_i64(0, 30);
+        match rng.gen_usize(0, 4) {
+            0 => {
+                set.insert(x);
+                let i = naive.partition_point(|&y| y <= x);
+                naive.insert(i, x);
+            }
+            1 => {
+                let found = naive.binary_search(&x).is_ok();
+                assert_eq!(set.insert_unique(x), !found);
+                if !found {
+                    let i = naive.partition_point(|&y| y < x);
+                    naive.insert(i, x);
+                }
+            }
+            2 => {
+                let i = naive.partition_point(|&y| y < x);
+                let found = naive.get(i) == Some(&x);
+                if found {
+                    naive.remove(i);
+                }
+                assert_eq!(set.remove(&x), found);
+            }
+            _ => {
+                assert_eq!(set.lower_bound(&x), naive.partition_point(|&y| y < x));
+                assert_eq!(set.upper_bound(&x), naive.partition_point(|&y| y <= x));
+                assert_eq!(set.count(&x), naive.iter().filter(|&&y| y == x).count());
+                let k = rng.gen_usize(0, naive.len() + 1);
+                assert_eq!(set.kth(k), naive.get(k));
+            }
+        }
+        assert_eq!(set.len(), naive.len());
+    }
+    assert_eq!(set.iter().copied().collect::<Vec<_>>(), naive);
+}
+
+#[test]
+fn test_implicit_treap_split_merge() {
+    use crate::data_structure::lazy_segtree::RangeAddRangeMin;
+
+    let mut rng = Xoshiro256::new(8192);
+    let mut treap = ImplicitTreap::<RangeAddRangeMin>::new();
+    let mut naive = (0..20).map(|_| rng.gen_i64(0, 100)).collect::<Vec<_>>();
+    let root = treap.build(naive.clone());
+    treap.set_root(root);
+    let q = if cfg!(miri) { 100 } else { 1000 };
+    for _ in 0..q {
+        let n = naive.len();
+        let k = rng.gen_usize(0, n + 1);
+        let (a, b) = treap.split(treap.root(), k);
+        assert_eq!(treap.to_vec_of(a), naive[..k]);
+        assert_eq!(treap.to_vec_of(b), naive[k..]);
+        assert_eq!((treap.len_of(a), treap.len_of(b)), (k, n - k));
+        assert_eq!(treap.prod_of(b), naive[k..].iter().copied().min().unwrap_or(i64::MAX));
+        match rng.gen_usize(0, 3) {
+            0 => {
+                // 前後を入れ替えてつなぐ
+                let root = treap.merge(b, a);
+                treap.set_root(root);
+                naive.rotate_left(k);
+            }
+            1 => {
+                // 別に作った木を間に挟む
+                let xs = (0..rng.gen_usize(0, 5)).map(|_| rng.gen_i64(0, 100)).collect::<Vec<_>>();
+                let c = treap.build(xs.clone());
+                let ac = treap.merge(a, c);
+                let root = treap.merge(ac, b);
+                treap.set_root(root);
+                naive.splice(k..k, xs);
+            }
+            _ => {
+                // 後ろを捨てる
+                if n > 30 {
+                    treap.free_tree(b);
+                    treap.set_root(a);
+                    naive.truncate(k);
+                } else {
+                    let root = treap.merge(a, b);
+                    treap.set_root(root);
+                }
+            }
+        }
+        assert_eq!(treap.to_vec(), naive);
+    }
+}
+
+#[test]
+fn test_treap_multiset_split_merge() {
+    let mut rng = Xoshiro256::new(8192);
+    let mut set = TreapMultiSet::new();
+    let mut naive = vec![];
+    for _ in 0..30 {
+        let x = rng.gen_i64(0, 50);
+        set.insert(x);
+        naive.push(x);
+    }
+    naive.sort();
+    let q = if cfg!(miri) { 100 } else { 1000 };
+    for _ in 0..q {
+        let x = rng.gen_i64(-5, 55);
+        let k = naive.partition_point(|&y| y < x);
+        let (a, b) = set.split_by_key(set.root(), &x);
+        assert_eq!(set.iter_of(a).copied().collect::<Vec<_>>(), naive[..k]);
+        assert_eq!(set.iter_of(b).copied().collect::<Vec<_>>(), naive[k..]);
+        assert_eq!((set.len_of(a), set.len_of(b)), (k, naive.len() - k));
+        assert_eq!(set.max_of(a), naive[..k].last());
+        assert_eq!(set.min_of(b), naive[k..].first());
+        // x以上の側だけに挿入してからつなぎ直す
+        set.set_root(b);
+        let y = x.max(0) + rng.gen_i64(0, 5);
+        set.insert(y);
+        let root = set.merge(a, set.root());
+        set.set_root(root);
+        let i = naive.partition_point(|&z| z <= y);
+        naive.insert(i, y);
+        if naive.len() > 60 {
+            let x = naive[naive.len() / 2];
+            let (a, b) = set.split_by_key(set.root(), &x);
+            set.set_root(a);
+            naive.truncate(naive.partition_point(|&z| z < x));
+            assert_eq!(set.len_of(b) + naive.len(), 61);
+            set.free_tree(b);
+        }
+        assert_eq!(set.iter().copied().collect::<Vec<_>>(), naive);
+    }
+}
@@ -0,0 +1,480 @@
+use cargo_snippet::snippet;
+#[cfg(test)]
+use crate::rand::xoshiro256::Xoshiro256;
+
+/// Union-Find (素集合データ構造)
+/// union by size と経路圧縮により，各操作ならしO(α(n))
+#[snippet("r3yohei_UnionFind")]
+#[derive(Clone, Debug)]
+pub struct UnionFind {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+}
+#[snippet("r3yohei_UnionFind")]
+impl UnionFind {
+    pub fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+        }
+    }
+
+    /// xの根
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        // 経路上の頂点をすべて根に直接つなぎ替える
+        let mut crt = x;
+        while self.parent[crt] != root {
+            let next = self.parent[crt];
+            self.parent[crt] = root;
+            crt = next;
+        }
+        root
+    }
+
+    /// xとyを併合し，併合が起きたかを返す
+    pub fn unite(&mut self, x: usize, y: usize) -> bool {
+        let (mut x, mut y) = (self.find(x), self.find(y));
+        if x == y {
+            return false;
+        }
+        if self.size[x] < self.size[y] {
+            std::mem::swap(&mut x, &mut y);
+        }
+        self.parent[y] = x;
+        self.size[x] += self.size[y];
+        true
+    }
+
+    pub fn same(&mut self, x: usize, y: usize) -> bool {
+        self.find(x) == self.find(y)
+    }
+
+    /// xが属する集合の大きさ
+    pub fn size(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.size[root]
+    }
+
+    /// 集合ごとに要素を列挙する
+    pub fn groups(&mut self) -> Vec<Vec<usize>> {
+        let n = self.parent.len();
+        let mut groups = vec![vec![]; n];
+        for x in 0..n {
+            let root = self.find(x);
+            groups[root].push(x);
+        }
+        groups.into_iter().filter(|g| !g.is_empty()).collect()
+    }
+}
+
+/// 重み付きUnion-Find
+/// 各要素にポテンシャルw(x)があり，w(y) - w(x) = d という関係を併合していく
+/// diff_weight[x]: w(x) - w(parent[x])
+#[snippet("r3yohei_WeightedUnionFind")]
+#[derive(Clone, Debug)]
+pub struct WeightedUnionFind {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    diff_weight: Vec<i64>,
+}
+#[snippet("r3yohei_WeightedUnionFind")]
+impl WeightedUnionFind {
+    pub fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+            diff_weight: vec![0; n],
+        }
+    }
+
+    pub fn find(&mut self, x: usize) -> usize {
+        if self.parent[x] == x {
+            return x;
+        }
+        // 根までの経路は短いので再帰でよい
+        let p = self.parent[x];
+        let root = self.find(p);
+        self.diff_weight[x] += self.diff_weight[p];
+        self.parent[x] = root;
+        root
+    }
+
+    /// w(x) - w(根)
+    pub fn weight(&mut self, x: usize) -> i64 {
+        self.find(x);
+        self.diff_weight[x]
+    }
+
+    /// w(y) - w(x) = d として併合する
+    /// 既に同じ集合なら何もせずfalseを返す(矛盾するかはdiffで確かめる)
+    pub fn unite(&mut self, x: usize, y: usize, d: i64) -> bool {
+        // 根同士の関係 w(ry) - w(rx) に直す
+        let mut d = d + self.weight(x) - self.weight(y);
+        let (mut x, mut y) = (self.find(x), self.find(y));
+        if x == y {
+            return false;
+        }
+        if self.size[x] < self.size[y] {
+            std::mem::swap(&mut x, &mut y);
+            d = -d;
+        }
+        self.parent[y] = x;
+        self.size[x] += self.size[y];
+        self.diff_weight[y] = d;
+        true
+    }
+
+    pub fn same(&mut self, x: usize, y: usize) -> bool {
+        self.find(x) == self.find(y)
+    }
+
+    /// w(y) - w(x)．同じ集合でなければNone
+    pub fn diff(&mut self, x: usize, y: usize) -> Option<i64> {
+        if self.same(x, y) {
+            Some(self.weight(y) - self.weight(x))
+        } else {
+            None
+        }
+    }
+
+    pub fn size(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.size[root]
+    }
+}
+
+/// 巻き戻し可能なUnion-Find
+/// 経路圧縮をしないので各操作O(logn)．オフラインの動的連結性判定などに使う
+#[snippet("r3yohei_RollbackUnionFind")]
+#[derive(Clone, Debug)]
+pub struct RollbackUnionFind {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    // 併合の履歴(親になった根, 子になった根)．併合が起きなかった操作は(!0, !0)
+    history: Vec<(usize, usize)>,
+}
+#[snippet("r3yohei_RollbackUnionFind")]
+impl RollbackUnionFind {
+    pub fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+            history: vec![],
+        }
+    }
+
+    pub fn find(&self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            x = self.parent[x];
+        }
+        x
+    }
+
+    pub fn unite(&mut self, x: usize, y: usize) -> bool {
+        let (mut x, mut y) = (self.find(x), self.find(y));
+        if x == y {
+            self.history.push((!0, !0));
+            return false;
+        }
+        if self.size[x] < self.size[y] {
+            std::mem::swap(&mut x, &mut y);
+        }
+        self.parent[y] = x;
+        self.size[x] += self.size[y];
+        self.history.push((x, y));
+        true
+    }
+
+    pub fn same(&self, x: usize, y: usize) -> bool {
+        self.find(x) == self.find(y)
+    }
+
+    pub fn size(&self, x: usize) -> usize {
+        self.size[self.find(x)]
+    }
+
+    /// 現在の状態を表す値．rollbackに渡すとこの状態に戻る
+    pub fn snapshot(&self) -> usize {
+        self.history.len()
+    }
+
+    /// 直前のuniteを取り消す．取り消すものがなければfalse
+    pub fn undo(&mut self) -> bool {
+        match self.history.pop() {
+            Some((x, y)) => {
+                if x != !0 {
+                    self.parent[y] = y;
+                    self.size[x] -= self.size[y];
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn rollback(&mut self, snapshot: usize) {
+        while self.history.len() > snapshot {
+            self.undo();
+        }
+    }
+}
+
+/// 完全永続配列
+/// 完全二分木の葉に値を持ち，更新のたびに根から葉までの経路だけを複製する
+/// get, set O(logn)
+#[snippet("r3yohei_PersistentUnionFind")]
+#[derive(Clone, Debug)]
+struct PersistentArray<T> {
+    // 内部節点は子の番号，葉は値を持つ
+    child: Vec<[usize; 2]>,
+    value: Vec<T>,
+    log: usize,
+}
+#[snippet("r3yohei_PersistentUnionFind")]
+impl<T: Clone> PersistentArray<T> {
+    /// 初期配列aから作り，その根を返す
+    fn new(a: &[T]) -> (Self, usize) {
+        let mut log = 0;
+        while (1 << log) < a.len() {
+            log += 1;
+        }
+        let mut array = Self { child: vec![], value: vec![], log };
+        let root = array.build(a, 0, log);
+        (array, root)
+    }
+
+    fn build(&mut self, a: &[T], l: usize, depth: usize) -> usize {
+        let id = self.child.len();
+        if depth == 0 {
+            self.child.push([!0, !0]);
+            self.value.push(a[l.min(a.len() - 1)].clone());
+            return id;
+        }
+        self.child.push([!0, !0]);
+        self.value.push(a[0].clone());
+        let left = self.build(a, l, depth - 1);
+        let right = self.build(a, l + (1 << (depth - 1)), depth - 1);
+        self.child[id] = [left, right];
+        id
+    }
+
+    fn get(&self, root: usize, i: usize) -> &T {
+        let mut crt = root;
+        for d in (0..self.log).rev() {
+            crt = self.child[crt][i >> d & 1];
+        }
+        &self.value[crt]
+    }
+
+    /// rootの版のi番目をxにした新しい版の根を返す
+    fn set(&mut self, root: usize, i: usize, x: T) -> usize {
+        let mut path = vec![root];
+        for d in (0..self.log).rev() {
+            let crt = *path.last().unwrap();
+            path.push(self.child[crt][i >> d & 1]);
+        }
+        // 葉から順に複製する
+        let mut new_id = self.child.len();
+        self.child.push([!0, !0]);
+        self.value.push(x);
+        for d in 0..self.log {
+            let old = path[self.log - 1 - d];
+            let mut c = self.child[old];
+            c[i >> d & 1] = new_id;
+            new_id = self.child.len();
+            self.child.push(c);
+            self.value.push(self.value[old].clone());
+        }
+        new_id
+    }
+}
+
+/// 完全永続Union-Find
+/// 各版は番号で指定し，uniteは新しい版の番号を返す(最初の版は0)
+/// 経路圧縮をせずunion by sizeだけを使うので，各操作O(log^2n)
+#[snippet("r3yohei_PersistentUnionFind")]
+#[derive(Clone, Debug)]
+pub struct PersistentUnionFind {
+    // 根なら-(集合の大きさ)，そうでなければ親
+    data: PersistentArray<i64>,
+    roots: Vec<usize>,
+}
+#[snippet("r3yohei_PersistentUnionFind")]
+impl PersistentUnionFind {
+    pub fn new(n: usize) -> Self {
+        let (data, root) = PersistentArray::new(&vec![-1; n.max(1)]);
+        Self { data, roots: vec![root] }
+    }
+
+    pub fn find(&self, version: usize, mut x: usize) -> usize {
+        let root = self.roots[version];
+        while *self.data.get(root, x) >= 0 {
+            x = *self.data.get(root, x) as usize;
+        }
+        x
+    }
+
+    /// versionの版でxとyを併合した新しい版の番号を返す
+    pub fn unite(&mut self, version: usize, x: usize, y: usize) -> usize {
+        let (mut x, mut y) = (self.find(version, x), self.find(version, y));
+        let mut root = self.roots[version];
+        if x != y {
+            let (sx, sy) = (*self.data.get(root, x), *self.data.get(root, y));
+            if sx > sy {
+                std::mem::swap(&mut x, &mut y);
+            }
+            root = self.data.set(root, x, sx + sy);
+            root = self.data.set(root, y, x as i64);
+        }
+        self.roots.push(root);
+        self.roots.len() - 1
+    }
+
+    pub fn same(&self, version: usize, x: usize, y: usize) -> bool {
+        self.find(version, x) == self.find(version, y)
+    }
+
+    pub fn size(&self, version: usize, x: usize) -> usize {
+        let root = self.find(version, x);
+        -*self.data.get(self.roots[version], root) as usize
+    }
+}
+
+/// テスト用の愚直な素集合
+#[cfg(test)]
+#[derive(Clone)]
+struct NaiveUnionFind {
+    label: Vec<usize>,
+}
+#[cfg(test)]
+impl NaiveUnionFind {
+    fn new(n: usize) -> Self {
+        Self { label: (0..n).collect() }
+    }
+    fn unite(&mut self, x: usize, y: usize) -> bool {
+        let (lx, ly) = (self.label[x], self.label[y]);
+        for l in self.label.iter_mut() {
+            if *l == ly {
+                *l = lx;
+            }
+        }
+        lx != ly
+    }
+    fn same(&self, x: usize, y: usize) -> bool {
+        self.label[x] == self.label[y]
+    }
+    fn size(&self, x: usize) -> usize {
+        self.label.iter().filter(|&&l| l == self.label[x]).count()
+    }
+}
+
+#[test]
+fn test_union_find() {
+    let mut uf = UnionFind::new(5);
+    assert!(uf.unite(0, 1));
+    assert!(uf.unite(3, 4));
+    assert!(!uf.unite(1, 0));
+    assert!(uf.same(0, 1));
+    assert!(!uf.same(1, 3));
+    assert_eq!(uf.size(4), 2);
+    assert_eq!(uf.groups(), vec![vec![0, 1], vec![2], vec![3, 4]]);
+}
+
+#[test]
+fn test_union_find_random() {
+    let mut rng = Xoshiro256::new(8192);
+    for _ in 0..100 {
+        let n = rng.gen_usize(1, 30);
+        let mut uf = UnionFind::new(n);
+        let mut naive = NaiveUnionFind::new(n);
+        for _ in 0..100 {
+            let (x, y) = (rng.gen_usize(0, n), rng.gen_usize(0, n));
+            if rng.gen_bool(0.5) {
+                assert_eq!(uf.unite(x, y), naive.unite(x, y));
+            } else {
+                assert_eq!(uf.same(x, y), naive.same(x, y));
+                assert_eq!(uf.size(x), naive.size(x));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_weighted_union_find_random() {
+    let mut rng = Xoshiro256::new(8192);
+    for _ in 0..100 {
+        let n = rng.gen_usize(1, 30);
+        // 正解のポテンシャルを決めておき，それと矛盾しない関係だけを与える
+        let w = (0..n).map(|_| rng.gen_i64(-100, 100)).collect::<Vec<_>>();
+        let mut uf = WeightedUnionFind::new(n);
+        let mut naive = NaiveUnionFind::new(n);
+        for _ in 0..100 {
+            let (x, y) = (rng.gen_usize(0, n), rng.gen_usize(0, n));
+            if rng.gen_bool(0.5) {
+                assert_eq!(uf.unite(x, y, w[y] - w[x]), naive.unite(x, y));
+            } else if naive.same(x, y) {
+                assert_eq!(uf.diff(x, y), Some(w[y] - w[x]));
+                assert_eq!(uf.size(x), naive.size(x));
+            } else {
+                assert_eq!(uf.diff(x, y), None);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_rollback_union_find_random() {
+    let mut rng = Xoshiro256::new(8192);
+    for _ in 0..100 {
+        let n = rng.gen_usize(1, 30);
+        let mut uf = RollbackUnionFind::new(n);
+        // 各操作後の愚直な状態を積んでおき，巻き戻したらそれと比べる
+        let mut naive = vec![NaiveUnionFind::new(n)];
+        for _ in 0..100 {
+            let op = rng.gen_usize(0, 3);
+            if op == 0 {
+                let (x, y) = (rng.gen_usize(0, n), rng.gen_usize(0, n));
+                let mut next = naive.last().unwrap().clone();
+                assert_eq!(uf.unite(x, y), next.unite(x, y));
+                naive.push(next);
+            } else if op == 1 {
+                let snapshot = rng.gen_usize(0, uf.snapshot() + 1);
+                uf.rollback(snapshot);
+                naive.truncate(snapshot + 1);
+            }
+            let (x, y) = (rng.gen_usize(0, n), rng.gen_usize(0, n));
+            let last = naive.last().unwrap();
+            assert_eq!(uf.same(x, y), last.same(x, y));
+            assert_eq!(uf.size(x), last.size(x));
+        }
+        while uf.undo() {}
+        assert_eq!(uf.size(0), 1);
+    }
+}
+
+#[test]
+fn test_persistent_union_find_random() {
+    let mut rng = Xoshiro256::new(8192);
+    for _ in 0..100 {
+        let n = rng.gen_usize(1, 30);
+        let mut uf = PersistentUnionFind::new(n);
+        let mut naive = vec![NaiveUnionFind::new(n)];
+        for _ in 0..100 {
+            // 任意の過去の版から分岐させる
+            let version = rng.gen_usize(0, naive.len());
+            let (x, y) = (rng.gen_usize(0, n), rng.gen_usize(0, n));
+            let mut next = naive[version].clone();
+            next.unite(x, y);
+            assert_eq!(uf.unite(version, x, y), naive.len());
+            naive.push(next);
+            let version = rng.gen_usize(0, naive.len());
+            let (x, y) = (rng.gen_usize(0, n), rng.gen_usize(0, n));
+            assert_eq!(uf.same(version, x, y), naive[version].same(x, y));
+            assert_eq!(uf.size(version, x), naive[version].size(x));
+        }
+    }
+}
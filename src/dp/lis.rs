@@ -1,5 +1,6 @@
 use cargo_snippet::snippet;
 use superslice::*;
+use crate::data_structure::fenwick::FenwickTreeMax;
 
 /// 最長部分増加列(LIS)を求める O(NlogN)
 /// https://qiita.com/python_walker/items/d1e2be789f6e7a0851e5
@@ -22,8 +23,53 @@ fn lis(a: &[i64]) -> usize {
     ans
 }
 
+/// 重み付き最長部分増加列: 狭義単調増加な部分列のうち，重みw[i]の和の最大値 O(NlogN)
+/// 値で座標圧縮し，自分より小さい値で終わる部分列の最大値をフェニック木で求める
+#[snippet("r3yohei_weighted_lis")]
+#[snippet(include = "r3yohei_FenwickTreeMax")]
+pub fn weighted_lis(a: &[i64], w: &[i64]) -> i64 {
+    assert_eq!(a.len(), w.len());
+    let mut vals = a.to_vec();
+    vals.sort();
+    vals.dedup();
+    let mut bit = FenwickTreeMax::new(vals.len(), 0);
+    let mut ans = 0;
+    for (&ai, &wi) in a.iter().zip(w) {
+        let r = vals.lower_bound(&ai);
+        // 空の部分列(0)から始めてもよい
+        let best = bit.max(r) + wi;
+        bit.chmax(r, best);
+        ans = ans.max(best);
+    }
+    ans
+}
+
 #[test]
 fn test_lis() {
     let v = vec![4, 2, 3, 1, 5];
     assert_eq!(lis(&v), 3);
+}
+
+#[test]
+fn test_weighted_lis() {
+    use crate::rand::xoshiro256::Xoshiro256;
+
+    assert_eq!(weighted_lis(&[4, 2, 3, 1, 5], &[10, 1, 1, 1, 1]), 11);
+    assert_eq!(weighted_lis(&[1, 1, 1], &[2, 3, 4]), 4);
+    assert_eq!(weighted_lis(&[], &[]), 0);
+    let mut rng = Xoshiro256::new(8192);
+    for _ in 0..100 {
+        let n = rng.gen_usize(0, 12);
+        let a = (0..n).map(|_| rng.gen_i64(0, 6)).collect::<Vec<_>>();
+        let w = (0..n).map(|_| rng.gen_i64(-5, 10)).collect::<Vec<_>>();
+        // 部分列を全探索
+        let mut expected = 0;
+        for s in 0..1usize << n {
+            let idx = (0..n).filter(|&i| s >> i & 1 == 1).collect::<Vec<_>>();
+            if idx.windows(2).all(|p| a[p[0]] < a[p[1]]) {
+                expected = expected.max(idx.iter().map(|&i| w[i]).sum::<i64>());
+            }
+        }
+        assert_eq!(weighted_lis(&a, &w), expected);
+    }
 }
\ No newline at end of file
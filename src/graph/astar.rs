@@ -0,0 +1,240 @@
+use cargo_snippet::snippet;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::hash::Hash;
+
+/// 探索の結果．((コスト, 経路)か，着けなければNone, 展開した状態数)
+#[snippet("r3yohei_search_result")]
+pub type SearchResult<S> = (Option<(i64, Vec<S>)>, usize);
+
+/// A*探索
+/// neighbors(状態)で(次の状態, コスト)を列挙し，h(状態)でgoalまでのコストの下界を与える．コストは非負
+/// hが許容的(実際のコスト以下)なら最短経路が求まる．goalに着けなければNone
+/// checkがtrueなら，デバッグビルドでhが無矛盾(h(u) <= コスト(u, v) + h(v))か確かめる
+/// (コスト, 経路)と，展開した状態数を返す
+#[snippet("r3yohei_astar")]
+#[snippet(include = "r3yohei_search_result")]
+pub fn astar<S, F, H, G>(
+    start: S,
+    mut neighbors: F,
+    mut h: H,
+    mut goal: G,
+    check: bool,
+) -> SearchResult<S>
+where
+    S: Hash + Eq + Clone,
+    F: FnMut(&S) -> Vec<(S, i64)>,
+    H: FnMut(&S) -> i64,
+    G: FnMut(&S) -> bool,
+{
+    let inf = 1 << 60;
+    // 状態に番号を振り，番号で距離などを持つ
+    let mut id = HashMap::new();
+    id.insert(start.clone(), 0);
+    let mut hs = vec![h(&start)];
+    let mut states = vec![start];
+    let mut dist = vec![0];
+    let mut prev = vec![!0];
+    let mut bh = BinaryHeap::new();
+    bh.push((Reverse(hs[0]), 0));
+    let mut expanded = 0;
+    while let Some((Reverse(f), crt)) = bh.pop() {
+        if dist[crt] + hs[crt] < f { continue; }
+        if goal(&states[crt]) {
+            let mut path = vec![];
+            let mut tt = crt;
+            while tt != !0 {
+                path.push(states[tt].clone());
+                tt = prev[tt];
+            }
+            path.reverse();
+            return (Some((dist[crt], path)), expanded);
+        }
+        expanded += 1;
+        for (to, w) in neighbors(&states[crt]) {
+            let j = match id.get(&to) {
+                Some(&j) => j,
+                None => {
+                    id.insert(to.clone(), states.len());
+                    hs.push(h(&to));
+                    states.push(to);
+                    dist.push(inf);
+                    prev.push(!0);
+                    states.len() - 1
+                }
+            };
+            if check {
+                debug_assert!(hs[crt] <= w + hs[j], "heuristic is not consistent");
+            }
+            // 無矛盾でないhでは展開済みの状態が更新されうるが，そのときは再びキューに入れる
+            if dist[crt] + w < dist[j] {
+                dist[j] = dist[crt] + w;
+                prev[j] = crt;
+                bh.push((Reverse(dist[j] + hs[j]), j));
+            }
+        }
+    }
+
+    (None, expanded)
+}
+
+/// 二次元グリッド上のA* ('.'のマスを上下左右に動く)
+/// マンハッタン距離をhにする
+#[snippet("r3yohei_astar_grid")]
+#[snippet(include = "r3yohei_astar")]
+pub fn astar_grid(c: &Vec<Vec<char>>, s: (usize, usize), t: (usize, usize)) -> SearchResult<(usize, usize)> {
+    let h = c.len();
+    let w = c[0].len();
+    const DIJ: [(usize, usize); 4] = [(1, 0), (0, 1), (!0, 0), (0, !0)];
+    let neighbors = |&(x, y): &(usize, usize)| {
+        DIJ.iter()
+            .map(|&(dx, dy)| (x.wrapping_add(dx), y.wrapping_add(dy)))
+            .filter(|&(to_x, to_y)| to_x < h && to_y < w && c[to_x][to_y] == '.')
+            .map(|to| (to, 1))
+            .collect()
+    };
+    let manhattan = |&(x, y): &(usize, usize)| (x.abs_diff(t.0) + y.abs_diff(t.1)) as i64;
+    astar(s, neighbors, manhattan, |&v| v == t, true)
+}
+
+/// IDA*探索
+/// f = コスト + h が閾値以下の範囲で深さ優先探索し，見つからなければ閾値を超えたfの最小値まで上げて繰り返す
+/// 訪問済みの状態を覚えないのでメモリは経路の長さ分しか使わない．経路上の状態には戻らない
+/// hは許容的であること．(コスト, 経路)と，展開した状態数を返す
+#[snippet("r3yohei_ida_star")]
+#[snippet(include = "r3yohei_search_result")]
+pub fn ida_star<S, F, H, G>(start: S, mut neighbors: F, mut h: H, mut goal: G) -> SearchResult<S>
+where
+    S: Hash + Eq + Clone,
+    F: FnMut(&S) -> Vec<(S, i64)>,
+    H: FnMut(&S) -> i64,
+    G: FnMut(&S) -> bool,
+{
+    let mut expanded = 0;
+    if goal(&start) {
+        return (Some((0, vec![start])), expanded);
+    }
+    let mut bound = h(&start);
+    loop {
+        let mut next_bound = i64::MAX;
+        let mut path = vec![start.clone()];
+        let mut on_path = HashSet::new();
+        on_path.insert(start.clone());
+        // (ここまでのコスト, 次の状態たち, 次に見る番号)
+        expanded += 1;
+        let mut stack = vec![(0, neighbors(&start), 0)];
+        while let Some((g, nexts, i)) = stack.last_mut() {
+            if *i == nexts.len() {
+                stack.pop();
+                on_path.remove(&path.pop().unwrap());
+                continue;
+            }
+            let (to, w) = nexts[*i].clone();
+            let g = *g + w;
+            *i += 1;
+            if on_path.contains(&to) { continue; }
+            let f = g + h(&to);
+            if f > bound {
+                next_bound = next_bound.min(f);
+                continue;
+            }
+            path.push(to.clone());
+            if goal(&to) {
+                return (Some((g, path)), expanded);
+            }
+            expanded += 1;
+            let nexts = neighbors(&to);
+            on_path.insert(to);
+            stack.push((g, nexts, 0));
+        }
+        if next_bound == i64::MAX {
+            return (None, expanded);
+        }
+        bound = next_bound;
+    }
+}
+
+#[test]
+fn test_astar_grid() {
+    use crate::graph::grid::Grid;
+
+    let c = vec![
+        vec!['.', '.', '.', '.', '.'],
+        vec!['.', '#', '#', '#', '.'],
+        vec!['.', '.', '.', '#', '.'],
+        vec!['#', '#', '.', '#', '.'],
+        vec!['.', '.', '.', '.', '.'],
+    ];
+    let (res, _) = astar_grid(&c, (2, 0), (4, 0));
+    let (cost, path) = res.unwrap();
+    assert_eq!(cost, 6);
+    assert_eq!(path.len(), 7);
+    assert_eq!(path[0], (2, 0));
+    assert_eq!(path[6], (4, 0));
+    let (res, _) = astar_grid(&c, (2, 0), (1, 2));
+    assert_eq!(res, None);
+
+    // Gridの番号を状態にする．h = 0 (ダイクストラ法)より展開する状態が少ない
+    let grid = Grid::new(c);
+    let (s, t) = (grid.id(0, 0), grid.id(4, 4));
+    let (dist, _) = grid.bfs(s, |&ch| ch == '.');
+    let neighbors = |&v: &usize| grid.neighbors(v).filter(|&to| grid.cells[to] == '.').map(|to| (to, 1)).collect();
+    let manhattan = |&v: &usize| {
+        let (x, y) = grid.pos(v);
+        (x.abs_diff(4) + y.abs_diff(4)) as i64
+    };
+    let (res, expanded) = astar(s, neighbors, manhattan, |&v| v == t, true);
+    let (res_zero, expanded_zero) = astar(s, neighbors, |_| 0, |&v| v == t, true);
+    assert_eq!(res.unwrap().0, dist[t]);
+    assert_eq!(res_zero.unwrap().0, dist[t]);
+    assert!(expanded < expanded_zero);
+}
+
+#[test]
+fn test_ida_star() {
+    use crate::graph::bfs_implicit::{bfs_implicit, restore_bfs_implicit};
+
+    // 8パズル．0が空きマス
+    let start = vec![8, 1, 3, 4, 0, 2, 7, 6, 5];
+    let solved = vec![1, 2, 3, 4, 5, 6, 7, 8, 0];
+    let neighbors = |s: &Vec<usize>| {
+        let p = s.iter().position(|&x| x == 0).unwrap();
+        let (x, y) = (p / 3, p % 3);
+        let mut res = vec![];
+        for &(dx, dy) in &[(1, 0), (0, 1), (!0, 0), (0, !0)] {
+            let (to_x, to_y) = (x.wrapping_add(dx), y.wrapping_add(dy));
+            if to_x < 3 && to_y < 3 {
+                let mut t = s.clone();
+                t.swap(p, to_x * 3 + to_y);
+                res.push((t, 1));
+            }
+        }
+        res
+    };
+    // 各数字の正しい位置とのマンハッタン距離の和
+    let manhattan = |s: &Vec<usize>| {
+        let mut sum = 0;
+        for (p, &v) in s.iter().enumerate() {
+            if v == 0 { continue; }
+            let q = v - 1;
+            sum += ((p / 3).abs_diff(q / 3) + (p % 3).abs_diff(q % 3)) as i64;
+        }
+        sum
+    };
+    let (goal, prev) = bfs_implicit(start.clone(), |s| neighbors(s).into_iter().map(|(t, _)| t).collect(), |s| s == &solved);
+    let expected = restore_bfs_implicit(&prev, &goal.unwrap()).len() as i64 - 1;
+    assert_eq!(expected, 14);
+
+    let (res, expanded_astar) = astar(start.clone(), neighbors, manhattan, |s| s == &solved, true);
+    assert_eq!(res.unwrap().0, expected);
+    let (res, expanded_ida) = ida_star(start.clone(), neighbors, manhattan, |s| s == &solved);
+    let (cost, path) = res.unwrap();
+    assert_eq!(cost, expected);
+    assert_eq!(path.len() as i64, expected + 1);
+    assert_eq!(path.last(), Some(&solved));
+    assert!(expanded_astar > 0 && expanded_ida > 0);
+
+    // 0 - 1 - 2 の道で，3には行けない
+    let (res, _) = ida_star(0, |&v: &usize| if v < 2 { vec![(v + 1, 1)] } else { vec![(v - 1, 1)] }, |_| 0, |&v| v == 3);
+    assert_eq!(res, None);
+}
@@ -0,0 +1,168 @@
+use cargo_snippet::snippet;
+use std::collections::VecDeque;
+
+/// 隣接リスト表現に対するベルマンフォード法
+/// 負の辺があってもよい O(VE)
+/// 返り値は(dist, prev, neg)で，neg[v]がtrueならsからvへの経路に負閉路が絡み，距離は-∞
+/// 到達できない頂点のdistはinf (1 << 60)
+#[snippet("r3yohei_bellman_ford")]
+pub fn bellman_ford(edges: &Vec<Vec<(usize, i64)>>, s: usize) -> (Vec<i64>, Vec<usize>, Vec<bool>) {
+    let inf = 1 << 60;
+    let n = edges.len();
+    let mut dist = vec![inf; n];
+    dist[s] = 0;
+    let mut prev = vec![!0; n];
+    let mut neg = vec![false; n];
+    // n-1回で確定しなかった頂点は負閉路の影響を受けている
+    // さらにn回回すと，負閉路から到達できる頂点すべてに伝播する
+    for i in 0..2 * n {
+        for frm in 0..n {
+            if dist[frm] == inf { continue; }
+            for &(to, w) in &edges[frm] {
+                if neg[frm] {
+                    neg[to] = true;
+                } else if dist[frm] + w < dist[to] {
+                    dist[to] = dist[frm] + w;
+                    prev[to] = frm;
+                    if i >= n - 1 {
+                        neg[to] = true;
+                    }
+                }
+            }
+        }
+    }
+
+    (dist, prev, neg)
+}
+
+/// SPFA (Shortest Path Faster Algorithm)
+/// 更新された頂点だけをキューで回すベルマンフォード法．最悪O(VE)だが，多くの場合速い
+/// 返り値はbellman_fordと同じ
+#[snippet("r3yohei_spfa")]
+pub fn spfa(edges: &Vec<Vec<(usize, i64)>>, s: usize) -> (Vec<i64>, Vec<usize>, Vec<bool>) {
+    let inf = 1 << 60;
+    let n = edges.len();
+    let mut dist = vec![inf; n];
+    dist[s] = 0;
+    let mut prev = vec![!0; n];
+    let mut neg = vec![false; n];
+    // cnt[v]: sからvへの暫定最短経路の辺数．n以上になったら負閉路を回っている
+    let mut cnt = vec![0; n];
+    let mut in_queue = vec![false; n];
+    let mut deque = VecDeque::new();
+    deque.push_back(s);
+    in_queue[s] = true;
+    let mut on_cycle = vec![];
+
+    while let Some(frm) = deque.pop_front() {
+        in_queue[frm] = false;
+        if neg[frm] { continue; }
+        for &(to, w) in &edges[frm] {
+            if dist[frm] + w < dist[to] && !neg[to] {
+                dist[to] = dist[frm] + w;
+                prev[to] = frm;
+                cnt[to] = cnt[frm] + 1;
+                if cnt[to] >= n {
+                    neg[to] = true;
+                    on_cycle.push(to);
+                } else if !in_queue[to] {
+                    in_queue[to] = true;
+                    deque.push_back(to);
+                }
+            }
+        }
+    }
+
+    // 負閉路から到達できる頂点にnegを伝播させる
+    while let Some(frm) = on_cycle.pop() {
+        for &(to, _) in &edges[frm] {
+            if !neg[to] {
+                neg[to] = true;
+                on_cycle.push(to);
+            }
+        }
+    }
+
+    (dist, prev, neg)
+}
+
+/// グラフ中の負閉路を1つ頂点列として返す
+/// 始点によらず，グラフのどこかにある負閉路を見つける O(VE)
+/// 返す頂点列は閉路をたどる順で，最後の頂点から最初の頂点への辺がある
+#[snippet("r3yohei_find_negative_cycle")]
+pub fn find_negative_cycle(edges: &Vec<Vec<(usize, i64)>>) -> Option<Vec<usize>> {
+    let n = edges.len();
+    // 全頂点に距離0の仮想始点から辺が張られているとみなす
+    let mut dist = vec![0; n];
+    let mut prev = vec![!0; n];
+    let mut last = !0;
+    for _ in 0..n {
+        last = !0;
+        for frm in 0..n {
+            for &(to, w) in &edges[frm] {
+                if dist[frm] + w < dist[to] {
+                    dist[to] = dist[frm] + w;
+                    prev[to] = frm;
+                    last = to;
+                }
+            }
+        }
+        if last == !0 {
+            return None;
+        }
+    }
+
+    // n回目でも更新された頂点からn回戻れば，必ず負閉路上にいる
+    let mut v = last;
+    for _ in 0..n {
+        v = prev[v];
+    }
+    let mut cycle = vec![v];
+    let mut u = prev[v];
+    while u != v {
+        cycle.push(u);
+        u = prev[u];
+    }
+    cycle.reverse();
+
+    Some(cycle)
+}
+
+#[test]
+fn test_bellman_ford() {
+    // 1 -> 2 -> 3 -> 1 が負閉路，4は負閉路から到達できる，5は到達できない
+    let edges = vec![
+        vec![(1, 4), (5, 2)],
+        vec![(2, -1)],
+        vec![(3, -1)],
+        vec![(1, -1), (4, 3)],
+        vec![],
+        vec![(6, -3)],
+        vec![],
+    ];
+    let expected = vec![false, true, true, true, true, false, false];
+    let (dist, _, neg) = bellman_ford(&edges, 0);
+    assert_eq!(neg, expected);
+    assert_eq!((dist[5], dist[6]), (2, -1));
+    let (dist, _, neg) = spfa(&edges, 0);
+    assert_eq!(neg, expected);
+    assert_eq!((dist[5], dist[6]), (2, -1));
+
+    let cycle = find_negative_cycle(&edges).unwrap();
+    assert_eq!(cycle.len(), 3);
+    let mut sum = 0;
+    for i in 0..cycle.len() {
+        let (frm, to) = (cycle[i], cycle[(i + 1) % cycle.len()]);
+        sum += edges[frm].iter().find(|&&(v, _)| v == to).unwrap().1;
+    }
+    assert!(sum < 0);
+
+    // 負閉路がなければ通常の最短経路
+    let edges = vec![vec![(1, 4), (2, 1)], vec![(3, -2)], vec![(1, 1)], vec![]];
+    assert_eq!(find_negative_cycle(&edges), None);
+    let (dist, prev, neg) = spfa(&edges, 0);
+    assert_eq!(dist, vec![0, 2, 1, 0]);
+    assert_eq!(prev, vec![!0, 2, 0, 1]);
+    assert!(neg.iter().all(|&b| !b));
+    assert_eq!(bellman_ford(&edges, 0).0, dist);
+}
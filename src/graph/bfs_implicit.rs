@@ -0,0 +1,129 @@
+use cargo_snippet::snippet;
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+/// 状態空間上のBFS
+/// 隣接リストを作らず，neighbors(状態)で次の状態を列挙する(順列やbitmaskのパズルなど)
+/// goal(状態)を満たす状態が見つかったらそれを返す．親へのポインタはHashMapに持つ
+#[snippet("r3yohei_bfs_implicit")]
+pub fn bfs_implicit<S, F, G>(start: S, mut neighbors: F, mut goal: G) -> (Option<S>, HashMap<S, Option<S>>)
+where
+    S: Hash + Eq + Clone,
+    F: FnMut(&S) -> Vec<S>,
+    G: FnMut(&S) -> bool,
+{
+    let mut prev = HashMap::new();
+    prev.insert(start.clone(), None);
+    let mut deque = VecDeque::new();
+    deque.push_back(start);
+    while let Some(crt) = deque.pop_front() {
+        if goal(&crt) {
+            return (Some(crt), prev);
+        }
+        for to in neighbors(&crt) {
+            if !prev.contains_key(&to) {
+                prev.insert(to.clone(), Some(crt.clone()));
+                deque.push_back(to);
+            }
+        }
+    }
+
+    (None, prev)
+}
+
+/// 状態空間上のBFS経路復元
+#[snippet("r3yohei_bfs_implicit")]
+pub fn restore_bfs_implicit<S: Hash + Eq + Clone>(prev: &HashMap<S, Option<S>>, t: &S) -> Vec<S> {
+    let mut path = vec![t.clone()];
+    let mut tt = t;
+    while let Some(Some(p)) = prev.get(tt) {
+        path.push(p.clone());
+        tt = p;
+    }
+    path.reverse();
+
+    path
+}
+
+/// 両側BFS (半分全列挙)
+/// 操作が可逆(neighborsで行き来できる)なとき，startとgoalの両方から1層ずつ広げて出会ったところでつなぐ
+/// 探索する状態数が片側BFSの平方根程度になる．startからgoalへの最短経路を返す
+#[snippet("r3yohei_bidirectional_bfs")]
+#[snippet(include = "r3yohei_bfs_implicit")]
+pub fn bidirectional_bfs<S, F>(start: S, goal: S, mut neighbors: F) -> Option<Vec<S>>
+where
+    S: Hash + Eq + Clone,
+    F: FnMut(&S) -> Vec<S>,
+{
+    // prev[0]: startから，prev[1]: goalからの親
+    let mut prev = [HashMap::new(), HashMap::new()];
+    prev[0].insert(start.clone(), None);
+    prev[1].insert(goal.clone(), None);
+    let mut frontier = [vec![start], vec![goal]];
+    let mut meet = None;
+    while meet.is_none() && !frontier[0].is_empty() && !frontier[1].is_empty() {
+        // 小さいほうの層を広げる
+        let side = if frontier[0].len() <= frontier[1].len() { 0 } else { 1 };
+        if let Some(crt) = frontier[side].iter().find(|&s| prev[side ^ 1].contains_key(s)) {
+            meet = Some(crt.clone());
+            break;
+        }
+        let mut next = vec![];
+        for crt in std::mem::take(&mut frontier[side]) {
+            for to in neighbors(&crt) {
+                if !prev[side].contains_key(&to) {
+                    prev[side].insert(to.clone(), Some(crt.clone()));
+                    if prev[side ^ 1].contains_key(&to) {
+                        meet = Some(to.clone());
+                    }
+                    next.push(to);
+                }
+            }
+            if meet.is_some() { break; }
+        }
+        frontier[side] = next;
+    }
+
+    let meet = meet?;
+    let mut path = restore_bfs_implicit(&prev[0], &meet);
+    let mut back = restore_bfs_implicit(&prev[1], &meet);
+    back.pop();
+    back.reverse();
+    path.extend(back);
+    Some(path)
+}
+
+#[test]
+fn test_bfs_implicit() {
+    // 隣り合う2つを入れ替えて[0, 1, 2, 3, 4]を整列させる(転倒数回)
+    let start = vec![3, 1, 4, 0, 2];
+    let neighbors = |s: &Vec<usize>| {
+        (0..s.len() - 1)
+            .map(|i| {
+                let mut t = s.clone();
+                t.swap(i, i + 1);
+                t
+            })
+            .collect::<Vec<_>>()
+    };
+    let sorted = vec![0, 1, 2, 3, 4];
+    let (goal, prev) = bfs_implicit(start.clone(), neighbors, |s| s == &sorted);
+    let goal = goal.unwrap();
+    let path = restore_bfs_implicit(&prev, &goal);
+    assert_eq!(path.len() - 1, 6);
+    assert_eq!(path[0], start);
+    assert_eq!(path.last(), Some(&sorted));
+
+    let path = bidirectional_bfs(start.clone(), sorted.clone(), neighbors).unwrap();
+    assert_eq!(path.len() - 1, 6);
+    assert_eq!(path[0], start);
+    assert_eq!(path.last(), Some(&sorted));
+    for w in path.windows(2) {
+        assert!(neighbors(&w[0]).contains(&w[1]));
+    }
+
+    // bitmaskで到達できない状態
+    let (goal, _) = bfs_implicit(0u32, |&s| vec![s ^ 0b11, s ^ 0b110], |&s| s == 1);
+    assert_eq!(goal, None);
+    assert_eq!(bidirectional_bfs(0u32, 1, |&s| vec![s ^ 0b11, s ^ 0b110]), None);
+}
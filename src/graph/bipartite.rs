@@ -0,0 +1,291 @@
+use cargo_snippet::snippet;
+use std::collections::VecDeque;
+use crate::graph::capacity_scaling_successive_shortest_path::{CapacityScalingSuccessiveShortestPath, Status};
+
+/// 二部グラフ判定(BFSによる2彩色)
+/// 二部グラフなら各頂点の色(0 or 1)を，そうでなければ奇閉路を頂点列で返す
+/// 奇閉路は最後の頂点から最初の頂点への辺がある O(V+E)
+#[snippet("r3yohei_bipartite_coloring")]
+pub fn bipartite_coloring(edges: &Vec<Vec<usize>>) -> Result<Vec<usize>, Vec<usize>> {
+    let n = edges.len();
+    let mut color = vec![!0; n];
+    let mut prev = vec![!0; n];
+    let mut depth = vec![0; n];
+    for s in 0..n {
+        if color[s] != !0 { continue; }
+        color[s] = 0;
+        let mut deque = VecDeque::new();
+        deque.push_back(s);
+        while let Some(crt) = deque.pop_front() {
+            for &to in &edges[crt] {
+                if color[to] == !0 {
+                    color[to] = color[crt] ^ 1;
+                    prev[to] = crt;
+                    depth[to] = depth[crt] + 1;
+                    deque.push_back(to);
+                } else if color[to] == color[crt] {
+                    // BFS木でcrtとtoから共通の祖先まで上ると，辺crt-toと合わせて奇閉路になる
+                    let (mut u, mut v) = (crt, to);
+                    let mut left = vec![];
+                    let mut right = vec![];
+                    while depth[u] > depth[v] {
+                        left.push(u);
+                        u = prev[u];
+                    }
+                    while depth[v] > depth[u] {
+                        right.push(v);
+                        v = prev[v];
+                    }
+                    while u != v {
+                        left.push(u);
+                        right.push(v);
+                        u = prev[u];
+                        v = prev[v];
+                    }
+                    left.push(u);
+                    right.reverse();
+                    left.extend(right);
+                    return Err(left);
+                }
+            }
+        }
+    }
+    Ok(color)
+}
+
+/// ホップクロフト･カープ法による二部グラフの最大マッチング O(E√V)
+/// 左側の頂点0..l，右側の頂点0..rの間に辺を張る
+/// match_l[u], match_r[v]はマッチング相手で，いなければ!0
+#[snippet("r3yohei_HopcroftKarp")]
+#[derive(Clone, Debug)]
+pub struct HopcroftKarp {
+    l: usize,
+    r: usize,
+    edges: Vec<Vec<usize>>,
+    pub match_l: Vec<usize>,
+    pub match_r: Vec<usize>,
+}
+#[snippet("r3yohei_HopcroftKarp")]
+impl HopcroftKarp {
+    pub fn new(l: usize, r: usize) -> Self {
+        Self {
+            l,
+            r,
+            edges: vec![vec![]; l],
+            match_l: vec![!0; l],
+            match_r: vec![!0; r],
+        }
+    }
+
+    pub fn add_edge(&mut self, u: usize, v: usize) {
+        self.edges[u].push(v);
+    }
+
+    /// 最大マッチングの大きさ
+    pub fn max_matching(&mut self) -> usize {
+        let mut size = self.match_l.iter().filter(|&&v| v != !0).count();
+        loop {
+            // 未マッチの左頂点からの交互路の長さで層に分ける
+            let mut dist = vec![!0; self.l];
+            let mut deque = VecDeque::new();
+            for (u, (d, &m)) in dist.iter_mut().zip(&self.match_l).enumerate() {
+                if m == !0 {
+                    *d = 0;
+                    deque.push_back(u);
+                }
+            }
+            let mut found = false;
+            while let Some(u) = deque.pop_front() {
+                for &v in &self.edges[u] {
+                    let w = self.match_r[v];
+                    if w == !0 {
+                        found = true;
+                    } else if dist[w] == !0 {
+                        dist[w] = dist[u] + 1;
+                        deque.push_back(w);
+                    }
+                }
+            }
+            if !found {
+                return size;
+            }
+            // 層に沿って増加路を探し，見つかったものを同時に使う
+            let mut it = vec![0; self.l];
+            for s in 0..self.l {
+                if self.match_l[s] == !0 && self.augment(s, &mut dist, &mut it) {
+                    size += 1;
+                }
+            }
+        }
+    }
+
+    // sからの増加路を非再帰DFSで探す
+    fn augment(&mut self, s: usize, dist: &mut Vec<usize>, it: &mut Vec<usize>) -> bool {
+        // 左頂点の列と，それぞれから進んだ右頂点の列
+        let mut left = vec![s];
+        let mut right = vec![];
+        while let Some(&u) = left.last() {
+            let mut advanced = false;
+            while it[u] < self.edges[u].len() {
+                let v = self.edges[u][it[u]];
+                let w = self.match_r[v];
+                if w == !0 {
+                    // 増加路が見つかったので，マッチングを入れ替える
+                    right.push(v);
+                    for (&u, &v) in left.iter().zip(right.iter()) {
+                        self.match_l[u] = v;
+                        self.match_r[v] = u;
+                    }
+                    return true;
+                }
+                if dist[w] == dist[u] + 1 {
+                    right.push(v);
+                    left.push(w);
+                    advanced = true;
+                    break;
+                }
+                it[u] += 1;
+            }
+            if !advanced {
+                // uからは増加路がないので，二度と通らない
+                dist[u] = !0;
+                left.pop();
+                if let Some(&p) = left.last() {
+                    right.pop();
+                    it[p] += 1;
+                }
+            }
+        }
+        false
+    }
+
+    /// マッチングに使う辺(左, 右)
+    pub fn matching(&self) -> Vec<(usize, usize)> {
+        (0..self.l)
+            .filter(|&u| self.match_l[u] != !0)
+            .map(|u| (u, self.match_l[u]))
+            .collect()
+    }
+
+    /// ケーニッヒの定理による最小頂点被覆(左側, 右側)
+    /// max_matching()の後に呼ぶ．大きさは最大マッチングと等しい
+    pub fn min_vertex_cover(&self) -> (Vec<usize>, Vec<usize>) {
+        // 未マッチの左頂点から交互路で行ける頂点を求める
+        let mut visited_l = vec![false; self.l];
+        let mut visited_r = vec![false; self.r];
+        let mut deque = VecDeque::new();
+        for (u, (visited, &m)) in visited_l.iter_mut().zip(&self.match_l).enumerate() {
+            if m == !0 {
+                *visited = true;
+                deque.push_back(u);
+            }
+        }
+        while let Some(u) = deque.pop_front() {
+            for &v in &self.edges[u] {
+                if visited_r[v] { continue; }
+                visited_r[v] = true;
+                let w = self.match_r[v];
+                if w != !0 && !visited_l[w] {
+                    visited_l[w] = true;
+                    deque.push_back(w);
+                }
+            }
+        }
+        // 行けなかった左頂点と行けた右頂点が被覆になる
+        (
+            (0..self.l).filter(|&u| !visited_l[u]).collect(),
+            (0..self.r).filter(|&v| visited_r[v]).collect(),
+        )
+    }
+
+    /// 最大独立集合(左側, 右側)．最小頂点被覆の補集合
+    pub fn max_independent_set(&self) -> (Vec<usize>, Vec<usize>) {
+        let (cover_l, cover_r) = self.min_vertex_cover();
+        let mut in_l = vec![true; self.l];
+        let mut in_r = vec![true; self.r];
+        for u in cover_l {
+            in_l[u] = false;
+        }
+        for v in cover_r {
+            in_r[v] = false;
+        }
+        (
+            (0..self.l).filter(|&u| in_l[u]).collect(),
+            (0..self.r).filter(|&v| in_r[v]).collect(),
+        )
+    }
+}
+
+/// 重み付き二部マッチング
+/// 重みの和が最大になるマッチング(完全マッチングとは限らない)の重みと，使う辺(左, 右)を返す
+/// 最小費用流に帰着する．始点から終点へ直接流す辺を張り，マッチしない分を逃がす
+#[snippet("r3yohei_weighted_bipartite_matching")]
+#[snippet(include = "r3yohei_capacity_scaling_successive_shortest_path")]
+pub fn weighted_bipartite_matching(l: usize, r: usize, edges: &[(usize, usize, i64)]) -> (i64, Vec<(usize, usize)>) {
+    let s = l + r;
+    let t = s + 1;
+    let mut mcf = CapacityScalingSuccessiveShortestPath::new(l + r + 2);
+    for u in 0..l {
+        mcf.add_edge(s, u, 0, 1, 0);
+    }
+    for v in 0..r {
+        mcf.add_edge(l + v, t, 0, 1, 0);
+    }
+    mcf.add_edge(s, t, 0, l as i64, 0);
+    // 重みが正の辺だけ使う価値がある
+    let mut ptrs = vec![];
+    for &(u, v, w) in edges {
+        if w > 0 {
+            ptrs.push((u, v, mcf.add_edge(u, l + v, 0, 1, -w)));
+        }
+    }
+    mcf.add_supply(s, l as i64);
+    mcf.add_demand(t, l as i64);
+    assert_eq!(mcf.solve(2), Status::OPTIMAL);
+    let matching = ptrs
+        .iter()
+        .filter(|(_, _, e)| mcf.get_edge_flow(e) == 1)
+        .map(|&(u, v, _)| (u, v))
+        .collect();
+    (-mcf.get_result_cost(), matching)
+}
+
+#[test]
+fn test_bipartite_coloring() {
+    let edges = vec![vec![1, 3], vec![0, 2], vec![1, 3], vec![0, 2]];
+    assert_eq!(bipartite_coloring(&edges), Ok(vec![0, 1, 0, 1]));
+    // 0 - 1 - 2 - 3 - 4 - 0 の五角形
+    let edges = vec![vec![1, 4], vec![0, 2], vec![1, 3], vec![2, 4], vec![3, 0]];
+    let cycle = bipartite_coloring(&edges).unwrap_err();
+    assert_eq!(cycle.len(), 5);
+    for i in 0..cycle.len() {
+        assert!(edges[cycle[i]].contains(&cycle[(i + 1) % cycle.len()]));
+    }
+}
+
+#[test]
+fn test_hopcroft_karp() {
+    let mut hk = HopcroftKarp::new(4, 4);
+    for &(u, v) in &[(0, 0), (0, 1), (1, 0), (2, 1), (2, 2), (3, 2)] {
+        hk.add_edge(u, v);
+    }
+    assert_eq!(hk.max_matching(), 3);
+    let matching = hk.matching();
+    assert_eq!(matching.len(), 3);
+    let (cover_l, cover_r) = hk.min_vertex_cover();
+    assert_eq!(cover_l.len() + cover_r.len(), 3);
+    for &(u, v) in &[(0, 0), (0, 1), (1, 0), (2, 1), (2, 2), (3, 2)] {
+        assert!(cover_l.contains(&u) || cover_r.contains(&v));
+    }
+    let (set_l, set_r) = hk.max_independent_set();
+    assert_eq!(set_l.len() + set_r.len(), 8 - 3);
+}
+
+#[test]
+fn test_weighted_bipartite_matching() {
+    let edges = vec![(0, 0, 3), (0, 1, 5), (1, 0, 4), (1, 1, 1), (2, 1, 2)];
+    let (weight, mut matching) = weighted_bipartite_matching(3, 2, &edges);
+    matching.sort();
+    assert_eq!(weight, 9);
+    assert_eq!(matching, vec![(0, 1), (1, 0)]);
+}
@@ -0,0 +1,92 @@
+use cargo_snippet::snippet;
+use crate::data_structure::union_find::UnionFind;
+
+/// ブルーフカ法により最小全域森のコストと使う辺を返す
+/// 各連結成分から出る最小の辺を同時に採用することを繰り返す．1回で成分数が半分以下になる
+/// O(ElogV)
+#[snippet("r3yohei_boruvka")]
+#[snippet(include = "r3yohei_UnionFind")]
+pub fn boruvka(n: usize, edges: &Vec<(usize, usize, i64)>) -> (i64, Vec<(usize, usize, i64)>) {
+    let mut uf = UnionFind::new(n);
+    let mut cost = 0;
+    let mut used = vec![];
+    loop {
+        // best[r]: 根rの成分から出る最小の辺の番号．同じ重みなら番号が小さいほうを選び，閉路を防ぐ
+        let mut best = vec![!0; n];
+        for (i, &(u, v, w)) in edges.iter().enumerate() {
+            let (ru, rv) = (uf.find(u), uf.find(v));
+            if ru == rv { continue; }
+            for r in [ru, rv] {
+                if best[r] == !0 || (w, i) < (edges[best[r]].2, best[r]) {
+                    best[r] = i;
+                }
+            }
+        }
+        let mut updated = false;
+        for r in 0..n {
+            if best[r] == !0 { continue; }
+            let (u, v, w) = edges[best[r]];
+            if uf.unite(u, v) {
+                cost += w;
+                used.push((u, v, w));
+                updated = true;
+            }
+        }
+        if !updated { break; }
+    }
+    (cost, used)
+}
+
+/// 完全グラフに対するブルーフカ法
+/// 辺を列挙せず，weight(u, v)で重みを計算する．点の座標から距離を求めるような幾何的な入力に使う
+/// O(V^2logV)
+#[snippet("r3yohei_boruvka_dense")]
+#[snippet(include = "r3yohei_UnionFind")]
+pub fn boruvka_dense(n: usize, weight: impl Fn(usize, usize) -> i64) -> (i64, Vec<(usize, usize, i64)>) {
+    let mut uf = UnionFind::new(n);
+    let mut cost = 0;
+    let mut used = vec![];
+    loop {
+        let root = (0..n).map(|v| uf.find(v)).collect::<Vec<_>>();
+        // best[r]: 根rの成分から出る最小の辺(w, u, v)
+        let mut best = vec![(i64::MAX, !0, !0); n];
+        for u in 0..n {
+            for v in u + 1..n {
+                if root[u] == root[v] { continue; }
+                let e = (weight(u, v), u, v);
+                for r in [root[u], root[v]] {
+                    if e < best[r] {
+                        best[r] = e;
+                    }
+                }
+            }
+        }
+        let mut updated = false;
+        for &(w, u, v) in &best {
+            if u != !0 && uf.unite(u, v) {
+                cost += w;
+                used.push((u, v, w));
+                updated = true;
+            }
+        }
+        if !updated { break; }
+    }
+    (cost, used)
+}
+
+#[test]
+fn test_boruvka() {
+    let edges = vec![(0, 1, 4), (0, 2, 1), (1, 2, 2), (1, 3, 5), (2, 3, 8), (4, 5, 3)];
+    let (cost, used) = boruvka(6, &edges);
+    assert_eq!(cost, 11);
+    assert_eq!(used.len(), 4);
+
+    // 平面上の点のマンハッタン距離による最小全域木
+    let p: Vec<(i64, i64)> = vec![(0, 0), (3, 0), (0, 1), (3, 3), (1, 1)];
+    let (cost, used) = boruvka_dense(p.len(), |u, v| {
+        let (a, b) = (p[u], p[v]);
+        (a.0 - b.0).abs() + (a.1 - b.1).abs()
+    });
+    assert_eq!(cost, 8);
+    assert_eq!(used.len(), 4);
+}
@@ -1,4 +1,6 @@
 use cargo_snippet::snippet;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
 
 #[snippet("r3yohei_capacity_scaling_successive_shortest_path")]
 type Flow = i64;
@@ -35,12 +37,25 @@ impl Edge {
     }
 }
 
+/// 主双対の証明書
+/// 各配列は辺を追加した順に並ぶ
+#[snippet("r3yohei_capacity_scaling_successive_shortest_path")]
+#[derive(Clone, Debug)]
+pub struct Certificate {
+    pub flow: Vec<Flow>,
+    pub potential: Vec<Cost>,
+    pub reduced_cost: Vec<Cost>,
+}
+
 #[snippet("r3yohei_capacity_scaling_successive_shortest_path")]
 pub struct CapacityScalingSuccessiveShortestPath {
     n: usize,
     g: Vec<Vec<Edge>>,
     b: Vec<Flow>,
     p: Vec<Cost>,
+    // 追加した辺と，解く前の供給量(証明書の検証用)
+    es: Vec<EdgePtr>,
+    supply: Vec<Flow>,
 }
 
 #[snippet("r3yohei_capacity_scaling_successive_shortest_path")]
@@ -51,6 +66,8 @@ impl CapacityScalingSuccessiveShortestPath {
             g: vec![vec![]; n],
             b: vec![Flow::default(); n],
             p: vec![Cost::default(); n],
+            es: vec![],
+            supply: vec![Flow::default(); n],
         }
     }
 
@@ -78,11 +95,13 @@ impl CapacityScalingSuccessiveShortestPath {
             flow: Flow::default(),
             cost: -cost,
         });
+        self.es.push(EdgePtr { from, idx: fidx });
         EdgePtr { from, idx: fidx }
     }
 
     pub fn add_supply(&mut self, v: usize, amount: Flow) {
         self.b[v] += amount;
+        self.supply[v] += amount;
     }
 
     pub fn add_demand(&mut self, v: usize, amount: Flow) {
@@ -97,6 +116,123 @@ impl CapacityScalingSuccessiveShortestPath {
         self.p[v]
     }
 
+    /// 被約費用 cost + p[from] - p[to]
+    /// 最適解では，正なら流量は下限，負なら上限に張り付く
+    pub fn get_reduced_cost(&self, e: &EdgePtr) -> Cost {
+        let edge = &self.g[e.from][e.idx];
+        edge.cost + self.p[e.from] - self.p[edge.to]
+    }
+
+    fn get_lower_upper(&self, e: &EdgePtr) -> (Flow, Flow) {
+        let edge = &self.g[e.from][e.idx];
+        (-self.g[edge.to][edge.rev].cap, edge.cap)
+    }
+
+    /// 感度分析: 他の辺を変えずに辺eのコストだけを動かすとき，今の流れが最適のままであるコストの範囲[lo, hi]
+    /// 制限がない側はNone．solve()が最適解を返した後に使う O(ElogV)
+    /// 流量を増やせるならfrom -> toの閉路，減らせるならto -> fromの閉路が負にならない範囲
+    pub fn get_cost_range(&self, e: &EdgePtr) -> (Option<Cost>, Option<Cost>) {
+        let (lower, upper) = self.get_lower_upper(e);
+        let flow = self.get_edge_flow(e);
+        let (from, to) = (e.from, self.g[e.from][e.idx].to);
+        let lo = if flow < upper {
+            // to -> fromの最短路とeでできる閉路
+            self.residual_dist(to, e)[from].map(|d| -(d - self.p[to] + self.p[from]))
+        } else {
+            None
+        };
+        let hi = if lower < flow {
+            // from -> toの最短路とeの逆辺でできる閉路
+            self.residual_dist(from, e)[to].map(|d| d - self.p[from] + self.p[to])
+        } else {
+            None
+        };
+        (lo, hi)
+    }
+
+    // 辺eとその逆辺を除いた残余グラフで，sからの被約費用での最短距離
+    fn residual_dist(&self, s: usize, e: &EdgePtr) -> Vec<Option<Cost>> {
+        let edge = &self.g[e.from][e.idx];
+        let skip = |v: usize, ei: usize| (v, ei) == (e.from, e.idx) || (v, ei) == (edge.to, edge.rev);
+        let mut que = BinaryHeap::new();
+        let mut dist = vec![None; self.n];
+        dist[s] = Some(Cost::default());
+        que.push(Reverse((Cost::default(), s)));
+        while let Some(Reverse((d, v))) = que.pop() {
+            if dist[v].map_or(false, |dv| dv < d) {
+                continue;
+            }
+            for ei in 0..self.g[v].len() {
+                let (cap, cost) = self.residual_cap_cost(v, &self.g[v][ei]);
+                let to = self.g[v][ei].to;
+                if cap <= Flow::default() || skip(v, ei) {
+                    continue;
+                }
+                let cost = d + cost;
+                if dist[to].map_or(true, |dt| dt > cost) {
+                    dist[to] = Some(cost);
+                    que.push(Reverse((cost, to)));
+                }
+            }
+        }
+        dist
+    }
+
+    /// 辺流量･ポテンシャル･被約費用をまとめて返す
+    pub fn get_certificate(&self) -> Certificate {
+        Certificate {
+            flow: self.es.iter().map(|e| self.get_edge_flow(e)).collect(),
+            potential: self.p.clone(),
+            reduced_cost: self.es.iter().map(|e| self.get_reduced_cost(e)).collect(),
+        }
+    }
+
+    /// 双対問題の目的関数値
+    /// Σ_e min(rc_e * lower_e, rc_e * upper_e) - Σ_v p_v * b_v
+    /// 最適なら強双対性によりget_result_cost()と一致する
+    pub fn get_dual_cost(&self) -> Cost {
+        let mut result = Cost::default();
+        for e in self.es.iter() {
+            let (lower, upper) = self.get_lower_upper(e);
+            let rc = self.get_reduced_cost(e);
+            if rc > Cost::default() {
+                result += rc * Cost::from(lower);
+            } else if rc < Cost::default() {
+                result += rc * Cost::from(upper);
+            }
+        }
+        for v in 0..self.n {
+            result -= self.p[v] * Cost::from(self.supply[v]);
+        }
+        result
+    }
+
+    /// solve()の結果が最適であることを検証する
+    /// 容量制約･流量保存則･相補性条件を確認し，主問題と双対問題の値が一致するかを見る
+    pub fn verify(&self) -> bool {
+        let mut excess = self.supply.clone();
+        for e in self.es.iter() {
+            let (lower, upper) = self.get_lower_upper(e);
+            let flow = self.get_edge_flow(e);
+            let rc = self.get_reduced_cost(e);
+            // 容量制約
+            if flow < lower || upper < flow {
+                return false;
+            }
+            // 相補性条件: 流量を増やせる辺は rc >= 0，減らせる辺は rc <= 0
+            if (flow < upper && rc < Cost::default()) || (lower < flow && rc > Cost::default()) {
+                return false;
+            }
+            excess[e.from] -= flow;
+            excess[self.g[e.from][e.idx].to] += flow;
+        }
+        // 流量保存則
+        if excess.iter().any(|&x| x != Flow::default()) {
+            return false;
+        }
+        self.get_result_cost() == self.get_dual_cost()
+    }
+
     fn push(&mut self, e: EdgePtr, amount: Flow) {
         self.g[e.from][e.idx].flow += amount;
         let to = self.g[e.from][e.idx].to;
@@ -243,3 +379,82 @@ impl CapacityScalingSuccessiveShortestPath {
         result
     }
 }
+
+#[test]
+fn test_certificate() {
+    // 0 -> 3 へ流量4を流す
+    let mut mcf = CapacityScalingSuccessiveShortestPath::new(4);
+    let e = [
+        mcf.add_edge(0, 1, 0, 2, 1),
+        mcf.add_edge(0, 2, 0, 3, 3),
+        mcf.add_edge(1, 2, 0, 1, 1),
+        mcf.add_edge(1, 3, 1, 3, 4),
+        mcf.add_edge(2, 3, 0, 2, 1),
+    ];
+    mcf.add_supply(0, 4);
+    mcf.add_demand(3, 4);
+    assert_eq!(mcf.solve(2), Status::OPTIMAL);
+    assert_eq!(mcf.get_result_cost(), 18);
+    assert!(mcf.verify());
+    assert_eq!(mcf.get_dual_cost(), 18);
+    let cert = mcf.get_certificate();
+    assert_eq!(cert.flow.len(), e.len());
+    for (i, e) in e.iter().enumerate() {
+        assert_eq!(cert.flow[i], mcf.get_edge_flow(e));
+        assert_eq!(cert.reduced_cost[i], mcf.get_reduced_cost(e));
+    }
+}
+
+#[test]
+fn test_cost_range() {
+    use crate::rand::xoshiro256::Xoshiro256;
+
+    let mut rng = Xoshiro256::new(8192);
+    for _ in 0..50 {
+        let n = rng.gen_usize(2, 6);
+        let m = rng.gen_usize(1, 9);
+        // 実行可能になるよう，適当な流れから供給量を決める
+        let es = (0..m)
+            .map(|_| {
+                let (u, v) = (rng.gen_usize(0, n), rng.gen_usize(0, n));
+                let lower = rng.gen_i64(-2, 2);
+                let upper = lower + rng.gen_i64(0, 4);
+                (u, v, lower, upper, rng.gen_i64(-5, 6), rng.gen_i64(lower, upper + 1))
+            })
+            .collect::<Vec<_>>();
+        let mut supply = vec![0; n];
+        for &(u, v, _, _, _, f) in es.iter() {
+            supply[u] += f;
+            supply[v] -= f;
+        }
+        let solve = |costs: &[Cost]| {
+            let mut mcf = CapacityScalingSuccessiveShortestPath::new(n);
+            let ptrs = es
+                .iter()
+                .zip(costs)
+                .map(|(&(u, v, lower, upper, _, _), &c)| mcf.add_edge(u, v, lower, upper, c))
+                .collect::<Vec<_>>();
+            for (v, &b) in supply.iter().enumerate() {
+                mcf.add_supply(v, b);
+            }
+            assert_eq!(mcf.solve(2), Status::OPTIMAL);
+            (mcf, ptrs)
+        };
+        let costs = es.iter().map(|e| e.4).collect::<Vec<_>>();
+        let (mcf, ptrs) = solve(&costs);
+        let flow = ptrs.iter().map(|e| mcf.get_edge_flow(e)).collect::<Vec<_>>();
+        for (i, e) in ptrs.iter().enumerate() {
+            let (lo, hi) = mcf.get_cost_range(e);
+            // コストを動かして解き直し，今の流れの費用が最適値と一致するかで判定する
+            for c in -30..=30 {
+                let mut cs = costs.clone();
+                cs[i] = c;
+                let (other, _) = solve(&cs);
+                let cur = flow.iter().zip(&cs).map(|(f, c)| f * c).sum::<Cost>();
+                let expected = cur == other.get_result_cost();
+                let in_range = lo.map_or(true, |lo| lo <= c) && hi.map_or(true, |hi| c <= hi);
+                assert_eq!(in_range, expected);
+            }
+        }
+    }
+}
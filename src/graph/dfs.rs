@@ -12,4 +12,75 @@ fn dfs(crt: usize, pre: usize, edges: &Vec<Vec<usize>>, mut visited: &mut Vec<bo
         dfs(to, crt, edges, visited);
     }
     todo!("帰りがけの処理");
-}
\ No newline at end of file
+}
+
+/// 非再帰DFSで通知するイベント
+/// Enter(v, p): vに入った(行きがけ)，Exit(v, p): vから出た(帰りがけ)．pは親で，根なら!0
+#[snippet("r3yohei_dfs_iterative")]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DfsEvent {
+    Enter(usize, usize),
+    Exit(usize, usize),
+}
+
+/// 隣接リスト表現に対する非再帰DFS
+/// 再帰しないので10^5頂点のパスグラフでもスタックオーバーフローしない
+/// visitedは呼び出し側で持つので，全頂点から呼べば森全体をたどれる
+#[snippet("r3yohei_dfs_iterative")]
+pub fn dfs_iterative(edges: &Vec<Vec<usize>>, s: usize, visited: &mut Vec<bool>, mut f: impl FnMut(DfsEvent)) {
+    if visited[s] { return; }
+    // (頂点, 親, 次に見る辺の番号)
+    let mut stack = vec![(s, !0, 0)];
+    visited[s] = true;
+    f(DfsEvent::Enter(s, !0));
+    while let Some(&(crt, pre, i)) = stack.last() {
+        if i < edges[crt].len() {
+            let to = edges[crt][i];
+            stack.last_mut().unwrap().2 += 1;
+            if !visited[to] {
+                visited[to] = true;
+                f(DfsEvent::Enter(to, crt));
+                stack.push((to, crt, 0));
+            }
+        } else {
+            f(DfsEvent::Exit(crt, pre));
+            stack.pop();
+        }
+    }
+}
+
+#[test]
+fn test_dfs_iterative() {
+    let edges = vec![vec![1, 2], vec![0, 3], vec![0], vec![1]];
+    let mut visited = vec![false; 4];
+    let mut events = vec![];
+    dfs_iterative(&edges, 0, &mut visited, |e| events.push(e));
+    assert_eq!(
+        events,
+        vec![
+            DfsEvent::Enter(0, !0),
+            DfsEvent::Enter(1, 0),
+            DfsEvent::Enter(3, 1),
+            DfsEvent::Exit(3, 1),
+            DfsEvent::Exit(1, 0),
+            DfsEvent::Enter(2, 0),
+            DfsEvent::Exit(2, 0),
+            DfsEvent::Exit(0, !0),
+        ]
+    );
+
+    // 長いパスでもスタックオーバーフローしない
+    let n = 1_000_000;
+    let edges = (0..n).map(|i| if i + 1 < n { vec![i + 1] } else { vec![] }).collect::<Vec<_>>();
+    let mut visited = vec![false; n];
+    let mut depth = 0;
+    let mut max_depth = 0;
+    dfs_iterative(&edges, 0, &mut visited, |e| match e {
+        DfsEvent::Enter(_, _) => {
+            depth += 1;
+            max_depth = max_depth.max(depth);
+        }
+        DfsEvent::Exit(_, _) => depth -= 1,
+    });
+    assert_eq!(max_depth, n);
+}
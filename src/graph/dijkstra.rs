@@ -1,10 +1,12 @@
 use cargo_snippet::snippet;
 use std::collections::BinaryHeap;
 use std::cmp::Reverse;
+use crate::data_structure::radix_heap::RadixHeap;
+use crate::data_structure::indexed_heap::IndexedHeap;
 
 /// 隣接リスト表現に対するダイクストラ法
 #[snippet("r3yohei_dijkstra")]
-fn dijkstra(edges: &Vec<Vec<(usize, i64)>>, s: usize) -> (Vec<i64>, Vec<usize>) {
+pub fn dijkstra(edges: &Vec<Vec<(usize, i64)>>, s: usize) -> (Vec<i64>, Vec<usize>) {
     let inf = 1 << 60;
     let mut bh = BinaryHeap::new();
     bh.push((Reverse(0), s));
@@ -30,7 +32,8 @@ fn dijkstra(edges: &Vec<Vec<(usize, i64)>>, s: usize) -> (Vec<i64>, Vec<usize>)
 
 /// ダイクストラ法経路復元
 #[snippet("r3yohei_dijkstra")]
-fn restore_dijkstra(prev: &Vec<usize>, t: usize) -> Vec<usize> {
+#[snippet("r3yohei_restore_dijkstra")]
+pub fn restore_dijkstra(prev: &Vec<usize>, t: usize) -> Vec<usize> {
     let mut path = vec![];
     let mut tt = t;
     while tt != !0 {
@@ -40,4 +43,242 @@ fn restore_dijkstra(prev: &Vec<usize>, t: usize) -> Vec<usize> {
     path.reverse();
 
     path
-}
\ No newline at end of file
+}
+
+/// 基数ヒープを使ったダイクストラ法
+/// 辺の重みが非負整数のときに使え，BinaryHeapより定数倍が軽い
+#[snippet("r3yohei_dijkstra_radix_heap")]
+#[snippet(include = "r3yohei_radix_heap")]
+#[snippet(include = "r3yohei_restore_dijkstra")]
+pub fn dijkstra_radix_heap(edges: &Vec<Vec<(usize, u64)>>, s: usize) -> (Vec<u64>, Vec<usize>) {
+    let inf = u64::MAX;
+    let mut heap = RadixHeap::new();
+    heap.push(0, s);
+    let mut dist = vec![inf; edges.len()];
+    dist[s] = 0;
+    let mut prev = vec![!0; edges.len()];
+
+    while let Some((d, crt)) = heap.pop() {
+        if dist[crt] < d { continue; }
+        for &(to, w) in &edges[crt] {
+            if d + w < dist[to] {
+                dist[to] = d + w;
+                prev[to] = crt;
+                heap.push(dist[to], to);
+            }
+        }
+    }
+
+    (dist, prev)
+}
+
+/// 添字付きヒープを使ったダイクストラ法
+/// 距離が縮んだらヒープ内のキーを直接下げるので，ヒープの大きさが頂点数を超えない
+#[snippet("r3yohei_dijkstra_indexed_heap")]
+#[snippet(include = "r3yohei_IndexedHeap")]
+#[snippet(include = "r3yohei_restore_dijkstra")]
+pub fn dijkstra_indexed_heap(edges: &Vec<Vec<(usize, i64)>>, s: usize) -> (Vec<i64>, Vec<usize>) {
+    let inf = 1 << 60;
+    let mut heap = IndexedHeap::new(edges.len());
+    heap.push(s, 0);
+    let mut dist = vec![inf; edges.len()];
+    dist[s] = 0;
+    let mut prev = vec![!0; edges.len()];
+
+    while let Some((crt, d)) = heap.pop() {
+        for &(to, w) in &edges[crt] {
+            if d + w < dist[to] {
+                dist[to] = d + w;
+                prev[to] = crt;
+                heap.decrease_key(to, dist[to]);
+            }
+        }
+    }
+
+    (dist, prev)
+}
+
+/// 隣接行列表現に対するダイクストラ法
+/// O(V^2)なので，辺が多い(完全グラフなど)ときはヒープを使うより速い
+/// adj[i][j] = inf (1 << 60) なら辺がないものとする
+#[snippet("r3yohei_dijkstra_dense")]
+#[snippet(include = "r3yohei_restore_dijkstra")]
+pub fn dijkstra_dense(adj: &Vec<Vec<i64>>, s: usize) -> (Vec<i64>, Vec<usize>) {
+    let inf = 1 << 60;
+    let n = adj.len();
+    let mut dist = vec![inf; n];
+    dist[s] = 0;
+    let mut prev = vec![!0; n];
+    let mut used = vec![false; n];
+
+    for _ in 0..n {
+        // 未確定の頂点のうち最も近いものを確定させる
+        let mut crt = !0;
+        for v in 0..n {
+            if !used[v] && dist[v] < inf && (crt == !0 || dist[v] < dist[crt]) {
+                crt = v;
+            }
+        }
+        if crt == !0 { break; }
+        used[crt] = true;
+        for to in 0..n {
+            if adj[crt][to] < inf && dist[crt] + adj[crt][to] < dist[to] {
+                dist[to] = dist[crt] + adj[crt][to];
+                prev[to] = crt;
+            }
+        }
+    }
+
+    (dist, prev)
+}
+
+/// 多始点ダイクストラ法
+/// 始点集合ssのうち最も近いものからの距離を求める
+/// tを指定すると，tの距離が確定した時点で打ち切る(dist[t]以外は暫定値になる)
+#[snippet("r3yohei_dijkstra_multi_source")]
+#[snippet(include = "r3yohei_restore_dijkstra")]
+pub fn dijkstra_multi_source(edges: &Vec<Vec<(usize, i64)>>, ss: &[usize], t: Option<usize>) -> (Vec<i64>, Vec<usize>) {
+    let inf = 1 << 60;
+    let mut bh = BinaryHeap::new();
+    let mut dist = vec![inf; edges.len()];
+    let mut prev = vec![!0; edges.len()];
+    for &s in ss {
+        dist[s] = 0;
+        bh.push((Reverse(0), s));
+    }
+
+    while let Some((Reverse(d), crt)) = bh.pop() {
+        if dist[crt] < d { continue; }
+        if Some(crt) == t { break; }
+        for &(to, w) in &edges[crt] {
+            if d + w < dist[to] {
+                dist[to] = d + w;
+                prev[to] = crt;
+                bh.push((Reverse(dist[to]), to));
+            }
+        }
+    }
+
+    (dist, prev)
+}
+
+/// 最短経路の本数も数えるダイクストラ法
+/// 辺の重みは正とする(0の辺があると数え漏れる)
+/// cntの型は足し算ができればよく，ModIntを渡せばmodを取った本数になる
+/// 例: dijkstra_count(&edges, s, ModInt::zero(), ModInt::one())
+#[snippet("r3yohei_dijkstra_count")]
+#[snippet(include = "r3yohei_restore_dijkstra")]
+pub fn dijkstra_count<C>(edges: &Vec<Vec<(usize, i64)>>, s: usize, zero: C, one: C) -> (Vec<i64>, Vec<usize>, Vec<C>)
+where
+    C: Copy + std::ops::Add<Output = C>,
+{
+    let inf = 1 << 60;
+    let mut bh = BinaryHeap::new();
+    bh.push((Reverse(0), s));
+    let mut dist = vec![inf; edges.len()];
+    dist[s] = 0;
+    let mut prev = vec![!0; edges.len()];
+    let mut cnt = vec![zero; edges.len()];
+    cnt[s] = one;
+
+    while let Some((Reverse(d), crt)) = bh.pop() {
+        if dist[crt] < d { continue; }
+        for &(to, w) in &edges[crt] {
+            if d + w < dist[to] {
+                // より短い経路が見つかったので数え直す
+                dist[to] = d + w;
+                prev[to] = crt;
+                cnt[to] = cnt[crt];
+                bh.push((Reverse(dist[to]), to));
+            } else if d + w == dist[to] {
+                cnt[to] = cnt[to] + cnt[crt];
+            }
+        }
+    }
+
+    (dist, prev, cnt)
+}
+
+#[test]
+fn test_dijkstra_variants() {
+    use crate::util::modint::ModInt;
+
+    // 0 -> 1 -> 3, 0 -> 2 -> 3 の2本が最短
+    let edges = vec![
+        vec![(1, 1), (2, 1), (3, 5)],
+        vec![(3, 2)],
+        vec![(3, 2)],
+        vec![(4, 1)],
+        vec![],
+    ];
+    let (dist, prev) = dijkstra(&edges, 0);
+    assert_eq!(dist, vec![0, 1, 1, 3, 4]);
+
+    let edges_u64 = edges
+        .iter()
+        .map(|es| es.iter().map(|&(to, w)| (to, w as u64)).collect())
+        .collect();
+    let (dist_radix, prev_radix) = dijkstra_radix_heap(&edges_u64, 0);
+    assert_eq!(dist_radix, vec![0, 1, 1, 3, 4]);
+    assert_eq!(restore_dijkstra(&prev_radix, 4), restore_dijkstra(&prev, 4));
+    let (dist_indexed, prev_indexed) = dijkstra_indexed_heap(&edges, 0);
+    assert_eq!(dist_indexed, dist);
+    // 最短経路は2本あるので，どちらを通るかは取り出す順による
+    assert_eq!(restore_dijkstra(&prev_indexed, 4).len(), 4);
+
+    let inf = 1 << 60;
+    let mut adj = vec![vec![inf; 5]; 5];
+    for (frm, es) in edges.iter().enumerate() {
+        for &(to, w) in es {
+            adj[frm][to] = w;
+        }
+    }
+    let (dist_dense, _) = dijkstra_dense(&adj, 0);
+    assert_eq!(dist_dense, dist);
+
+    let (dist, prev) = dijkstra_multi_source(&edges, &[2, 3], None);
+    assert_eq!(dist, vec![inf, inf, 0, 0, 1]);
+    assert_eq!(restore_dijkstra(&prev, 4), vec![3, 4]);
+    let (dist, prev) = dijkstra_multi_source(&edges, &[0], Some(3));
+    assert_eq!(dist[3], 3);
+    assert_eq!(restore_dijkstra(&prev, 3).len(), 3);
+
+    let (_, _, cnt) = dijkstra_count(&edges, 0, 0u64, 1u64);
+    assert_eq!(cnt, vec![1, 1, 1, 2, 2]);
+    let (_, _, cnt) = dijkstra_count(&edges, 0, ModInt::zero(), ModInt::one());
+    assert_eq!(cnt[4].value(), 2);
+}
+
+// cargo test --release bench_dijkstra -- --ignored --nocapture で実行時間を比べる
+#[test]
+#[ignore]
+fn bench_dijkstra() {
+    use crate::rand::xoshiro256::Xoshiro256;
+    use std::time::Instant;
+
+    let mut rng = Xoshiro256::new(8192);
+    let (n, m) = (200_000, 1_000_000);
+    let mut edges = vec![vec![]; n];
+    for _ in 0..m {
+        let (u, v) = (rng.gen_usize(0, n), rng.gen_usize(0, n));
+        edges[u].push((v, rng.gen_i64(1, 1_000_000_000)));
+    }
+    let edges_u64 = edges
+        .iter()
+        .map(|es| es.iter().map(|&(to, w)| (to, w as u64)).collect())
+        .collect();
+
+    let start = Instant::now();
+    let (dist, _) = dijkstra(&edges, 0);
+    eprintln!("BinaryHeap:  {:?}", start.elapsed());
+    let start = Instant::now();
+    let (dist_radix, _) = dijkstra_radix_heap(&edges_u64, 0);
+    eprintln!("RadixHeap:   {:?}", start.elapsed());
+    let start = Instant::now();
+    let (dist_indexed, _) = dijkstra_indexed_heap(&edges, 0);
+    eprintln!("IndexedHeap: {:?}", start.elapsed());
+
+    let inf = 1 << 60;
+    assert!(dist.iter().zip(&dist_radix).all(|(&d, &e)| if d == inf { e == u64::MAX } else { d as u64 == e }));
+    assert_eq!(dist, dist_indexed);
+}
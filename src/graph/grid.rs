@@ -0,0 +1,313 @@
+use cargo_snippet::snippet;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+
+/// 上下左右
+#[snippet("r3yohei_grid")]
+pub const DIR4: [(isize, isize); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+/// 斜めを含む8近傍
+#[snippet("r3yohei_grid")]
+pub const DIR8: [(isize, isize); 8] = [
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+];
+/// 桂馬飛び(チェスのナイト)
+#[snippet("r3yohei_grid")]
+pub const KNIGHT: [(isize, isize); 8] = [
+    (2, 1),
+    (1, 2),
+    (-1, 2),
+    (-2, 1),
+    (-2, -1),
+    (-1, -2),
+    (1, -2),
+    (2, -1),
+];
+
+/// 二次元グリッド
+/// 頂点は x * w + y の一次元indexで扱うので，経路復元はrestore_bfs/restore_dijkstraがそのまま使える
+/// 通れるかどうか，移動コストはクロージャで渡す
+#[snippet("r3yohei_grid")]
+#[derive(Clone, Debug)]
+pub struct Grid<T> {
+    pub h: usize,
+    pub w: usize,
+    pub cells: Vec<T>,
+    dir: Vec<(isize, isize)>,
+    torus: bool,
+}
+#[snippet("r3yohei_grid")]
+impl<T> Grid<T> {
+    pub fn new(c: Vec<Vec<T>>) -> Self {
+        let h = c.len();
+        let w = if h == 0 { 0 } else { c[0].len() };
+        Self {
+            h,
+            w,
+            cells: c.into_iter().flatten().collect(),
+            dir: DIR4.to_vec(),
+            torus: false,
+        }
+    }
+
+    /// 近傍の取り方を変える(DIR4, DIR8, KNIGHTや任意の移動)
+    pub fn with_dir(mut self, dir: &[(isize, isize)]) -> Self {
+        self.dir = dir.to_vec();
+        self
+    }
+
+    /// 端と端をつなげる(トーラス)
+    pub fn with_torus(mut self, torus: bool) -> Self {
+        self.torus = torus;
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.h * self.w
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn id(&self, x: usize, y: usize) -> usize {
+        x * self.w + y
+    }
+
+    pub fn pos(&self, id: usize) -> (usize, usize) {
+        (id / self.w, id % self.w)
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> &T {
+        &self.cells[self.id(x, y)]
+    }
+
+    /// idから(dx, dy)だけ動いた先
+    /// 範囲外ならNone(トーラスなら折り返す)
+    pub fn step(&self, id: usize, (dx, dy): (isize, isize)) -> Option<usize> {
+        let (x, y) = self.pos(id);
+        let (to_x, to_y) = (x as isize + dx, y as isize + dy);
+        if self.torus {
+            let to_x = to_x.rem_euclid(self.h as isize) as usize;
+            let to_y = to_y.rem_euclid(self.w as isize) as usize;
+            Some(self.id(to_x, to_y))
+        } else if 0 <= to_x && to_x < self.h as isize && 0 <= to_y && to_y < self.w as isize {
+            Some(self.id(to_x as usize, to_y as usize))
+        } else {
+            None
+        }
+    }
+
+    /// idの近傍(範囲内のもの)
+    pub fn neighbors(&self, id: usize) -> impl Iterator<Item = usize> + '_ {
+        self.dir.iter().filter_map(move |&d| self.step(id, d))
+    }
+
+    /// 通れるマスだけを頂点とする隣接リスト
+    pub fn to_edges(&self, passable: impl Fn(&T) -> bool) -> Vec<Vec<usize>> {
+        let mut edges = vec![vec![]; self.len()];
+        for (v, cell) in self.cells.iter().enumerate() {
+            if !passable(cell) {
+                continue;
+            }
+            for to in self.neighbors(v) {
+                if passable(&self.cells[to]) {
+                    edges[v].push(to);
+                }
+            }
+        }
+        edges
+    }
+
+    /// グリッド上のBFS
+    /// 未訪問の距離は-1，prevの!0は未設定を示す
+    pub fn bfs(&self, s: usize, passable: impl Fn(&T) -> bool) -> (Vec<i64>, Vec<usize>) {
+        let mut deque = VecDeque::new();
+        deque.push_back(s);
+        let mut dist = vec![-1; self.len()];
+        dist[s] = 0;
+        let mut prev = vec![!0; self.len()];
+        while let Some(crt) = deque.pop_front() {
+            for to in self.neighbors(crt) {
+                if dist[to] == -1 && passable(&self.cells[to]) {
+                    dist[to] = dist[crt] + 1;
+                    prev[to] = crt;
+                    deque.push_back(to);
+                }
+            }
+        }
+
+        (dist, prev)
+    }
+
+    /// グリッド上の01BFS
+    /// cost(frm, to)がNoneなら移動できない．コストは0か1
+    pub fn zero_one_bfs(
+        &self,
+        s: usize,
+        cost: impl Fn(usize, usize) -> Option<i64>,
+    ) -> (Vec<i64>, Vec<usize>) {
+        const INF: i64 = 1 << 60;
+        let mut dist = vec![INF; self.len()];
+        dist[s] = 0;
+        let mut prev = vec![!0; self.len()];
+        let mut deque = VecDeque::new();
+        deque.push_back(s);
+        while let Some(frm) = deque.pop_front() {
+            for to in self.neighbors(frm) {
+                let c = match cost(frm, to) {
+                    Some(c) => c,
+                    None => continue,
+                };
+                let d = dist[frm] + c;
+                if d < dist[to] {
+                    dist[to] = d;
+                    prev[to] = frm;
+                    if c == 0 {
+                        deque.push_front(to);
+                    } else {
+                        deque.push_back(to);
+                    }
+                }
+            }
+        }
+
+        (dist, prev)
+    }
+
+    /// グリッド上のダイクストラ法
+    /// cost(frm, to)がNoneなら移動できない．コストは非負
+    pub fn dijkstra(
+        &self,
+        s: usize,
+        cost: impl Fn(usize, usize) -> Option<i64>,
+    ) -> (Vec<i64>, Vec<usize>) {
+        const INF: i64 = 1 << 60;
+        let mut bh = BinaryHeap::new();
+        bh.push((Reverse(0), s));
+        let mut dist = vec![INF; self.len()];
+        dist[s] = 0;
+        let mut prev = vec![!0; self.len()];
+        while let Some((Reverse(d), crt)) = bh.pop() {
+            if dist[crt] < d {
+                continue;
+            }
+            for to in self.neighbors(crt) {
+                if let Some(c) = cost(crt, to) {
+                    if d + c < dist[to] {
+                        dist[to] = d + c;
+                        prev[to] = crt;
+                        bh.push((Reverse(dist[to]), to));
+                    }
+                }
+            }
+        }
+
+        (dist, prev)
+    }
+
+    /// グリッド上のDFS
+    /// 再帰しないので大きなグリッドでもスタックオーバーフローしない
+    /// sから行けるマスをtrueにして返す
+    pub fn dfs(&self, s: usize, passable: impl Fn(&T) -> bool) -> Vec<bool> {
+        let mut visited = vec![false; self.len()];
+        let mut stack = vec![s];
+        visited[s] = true;
+        while let Some(crt) = stack.pop() {
+            for to in self.neighbors(crt) {
+                if !visited[to] && passable(&self.cells[to]) {
+                    visited[to] = true;
+                    stack.push(to);
+                }
+            }
+        }
+        visited
+    }
+
+    /// 壁にぶつかるまで止まれないときに通るマスをtrueにして返す
+    /// トーラスでは一周して戻ってきたら止まる
+    pub fn bfs_until_wall(&self, s: usize, passable: impl Fn(&T) -> bool) -> Vec<bool> {
+        let mut deque = VecDeque::new();
+        let mut visited = vec![false; self.len()];
+        let mut stopped = vec![false; self.len()];
+        deque.push_back(s);
+        visited[s] = true;
+        stopped[s] = true;
+        while let Some(frm) = deque.pop_front() {
+            for &d in &self.dir {
+                let mut to = frm;
+                while let Some(next) = self.step(to, d) {
+                    if !passable(&self.cells[next]) || next == frm {
+                        break;
+                    }
+                    to = next;
+                    visited[to] = true;
+                }
+                // 初めて来た停止点なら，次の開始点に入れる
+                if !stopped[to] {
+                    stopped[to] = true;
+                    deque.push_back(to);
+                }
+            }
+        }
+        visited
+    }
+}
+
+#[test]
+fn test_grid() {
+    let c = vec![
+        "######".chars().collect::<Vec<_>>(),
+        "#....#".chars().collect::<Vec<_>>(),
+        "#.#..#".chars().collect::<Vec<_>>(),
+        "#..#.#".chars().collect::<Vec<_>>(),
+        "#....#".chars().collect::<Vec<_>>(),
+        "######".chars().collect::<Vec<_>>(),
+    ];
+    let grid = Grid::new(c);
+    let s = grid.id(1, 1);
+    let passable = |&c: &char| c == '.';
+
+    // bfs_grid.rsのbfs_until_wallと同じ結果になる
+    let visited = grid.bfs_until_wall(s, passable);
+    assert_eq!(visited.iter().filter(|&&v| v).count(), 12);
+
+    let (dist, prev) = grid.bfs(s, passable);
+    let t = grid.id(4, 4);
+    assert_eq!(dist[t], 6);
+    let mut path = vec![t];
+    while prev[*path.last().unwrap()] != !0 {
+        path.push(prev[*path.last().unwrap()]);
+    }
+    assert_eq!(path.len(), 7);
+
+    // 斜め移動はコスト0の01BFS
+    let grid = grid.with_dir(&DIR8);
+    let cost = |frm: usize, to: usize| {
+        if grid.cells[to] != '.' {
+            return None;
+        }
+        let ((fx, fy), (tx, ty)) = (grid.pos(frm), grid.pos(to));
+        Some(if fx != tx && fy != ty { 0 } else { 1 })
+    };
+    // (2, 2)が壁なので，斜めだけでは行けず2回まっすぐ動く
+    let (dist, _) = grid.zero_one_bfs(s, cost);
+    assert_eq!(dist[t], 2);
+    let (dist, _) = grid.dijkstra(s, cost);
+    assert_eq!(dist[grid.id(1, 4)], 1);
+    assert_eq!(grid.dfs(s, passable).iter().filter(|&&v| v).count(), 14);
+
+    // 3x3のトーラス上のナイトは斜めに1マス動くのと同じ
+    let grid = Grid::new(vec![vec![0; 3]; 3])
+        .with_dir(&KNIGHT)
+        .with_torus(true);
+    let (dist, _) = grid.bfs(0, |_| true);
+    assert_eq!(dist[grid.id(1, 1)], 1);
+    assert_eq!(dist[grid.id(0, 1)], 2);
+}
@@ -0,0 +1,180 @@
+use cargo_snippet::snippet;
+use std::fmt::Write;
+
+/// 辺リストの形式
+/// one_indexed: 頂点番号が1始まりか，weighted: 各辺に重みがあるか，directed: 有向か
+#[snippet("r3yohei_graph_io")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GraphFormat {
+    pub one_indexed: bool,
+    pub weighted: bool,
+    pub directed: bool,
+}
+#[snippet("r3yohei_graph_io")]
+impl GraphFormat {
+    pub fn new(one_indexed: bool, weighted: bool, directed: bool) -> Self {
+        Self { one_indexed, weighted, directed }
+    }
+}
+
+/// 空白区切りのトークン列からm本の辺 "u v" または "u v w" を読む
+/// 返す辺は0始まりの(u, v, w)で，重みなしならw = 1
+#[snippet("r3yohei_graph_io")]
+pub fn parse_edges<'a>(tokens: &mut impl Iterator<Item = &'a str>, m: usize, fmt: GraphFormat) -> Vec<(usize, usize, i64)> {
+    let offset = if fmt.one_indexed { 1 } else { 0 };
+    let mut next = || tokens.next().expect("too few tokens");
+    (0..m)
+        .map(|_| {
+            let u = next().parse::<usize>().unwrap() - offset;
+            let v = next().parse::<usize>().unwrap() - offset;
+            let w = if fmt.weighted { next().parse::<i64>().unwrap() } else { 1 };
+            (u, v, w)
+        })
+        .collect()
+}
+
+/// "N M" の後にM本の辺が続く入力を読み，(N, 辺リスト)を返す
+/// 木のように辺の本数が書かれない入力は，parse_edgesにN-1本を読ませる
+#[snippet("r3yohei_graph_io")]
+pub fn parse_graph(input: &str, fmt: GraphFormat) -> (usize, Vec<(usize, usize, i64)>) {
+    let mut tokens = input.split_whitespace();
+    let n = tokens.next().unwrap().parse::<usize>().unwrap();
+    let m = tokens.next().unwrap().parse::<usize>().unwrap();
+    let edges = parse_edges(&mut tokens, m, fmt);
+    (n, edges)
+}
+
+/// 辺リストから隣接リストを作る
+/// proconioで読んだ uv: [(Usize1, Usize1); m] なども，重みを付けてそのまま渡せる
+/// 無向の自己ループは1回だけ入れる
+#[snippet("r3yohei_graph_io")]
+pub fn to_adjacency(n: usize, edges: &[(usize, usize, i64)], directed: bool) -> Vec<Vec<usize>> {
+    let mut adj = vec![vec![]; n];
+    for &(u, v, _) in edges {
+        adj[u].push(v);
+        if !directed && u != v {
+            adj[v].push(u);
+        }
+    }
+    adj
+}
+
+/// 辺リストから重み付き隣接リストを作る
+#[snippet("r3yohei_graph_io")]
+pub fn to_weighted_adjacency(n: usize, edges: &[(usize, usize, i64)], directed: bool) -> Vec<Vec<(usize, i64)>> {
+    let mut adj = vec![vec![]; n];
+    for &(u, v, w) in edges {
+        adj[u].push((v, w));
+        if !directed && u != v {
+            adj[v].push((u, w));
+        }
+    }
+    adj
+}
+
+/// 重み付き隣接リストを辺リストに戻す
+/// 無向なら u <= v の向きの辺だけを取る
+#[snippet("r3yohei_graph_io")]
+pub fn to_edge_list(adj: &Vec<Vec<(usize, i64)>>, directed: bool) -> Vec<(usize, usize, i64)> {
+    let mut edges = vec![];
+    for (u, es) in adj.iter().enumerate() {
+        for &(v, w) in es {
+            if directed || u <= v {
+                edges.push((u, v, w));
+            }
+        }
+    }
+    edges
+}
+
+/// GraphvizのDOT形式で出力する (dot -Tpng などで画像にする)
+/// pathは頂点列(restore_bfs, restore_dijkstraの結果など)で，通る頂点と辺を赤くする
+/// highlightに入れた辺(u, v)も赤くする．頂点番号はfmt.one_indexedに合わせて表示する
+#[snippet("r3yohei_graph_to_dot")]
+#[snippet(include = "r3yohei_graph_io")]
+pub fn to_dot(n: usize, edges: &[(usize, usize, i64)], fmt: GraphFormat, path: &[usize], highlight: &[(usize, usize)]) -> String {
+    let offset = if fmt.one_indexed { 1 } else { 0 };
+    let mut marked = highlight.to_vec();
+    for w in path.windows(2) {
+        marked.push((w[0], w[1]));
+    }
+    // 無向なら逆向きでも同じ辺
+    let is_marked = |u: usize, v: usize| marked.iter().any(|&(a, b)| (a, b) == (u, v) || (!fmt.directed && (a, b) == (v, u)));
+    let (kind, arrow) = if fmt.directed { ("digraph", "->") } else { ("graph", "--") };
+    let mut dot = String::new();
+    writeln!(dot, "{} G {{", kind).unwrap();
+    for v in 0..n {
+        if path.contains(&v) {
+            writeln!(dot, "    {} [color=red, style=filled, fillcolor=mistyrose];", v + offset).unwrap();
+        } else {
+            writeln!(dot, "    {};", v + offset).unwrap();
+        }
+    }
+    for &(u, v, w) in edges {
+        let mut attrs = vec![];
+        if fmt.weighted {
+            attrs.push(format!("label=\"{}\"", w));
+        }
+        if is_marked(u, v) {
+            attrs.push("color=red, penwidth=2".to_string());
+        }
+        if attrs.is_empty() {
+            writeln!(dot, "    {} {} {};", u + offset, arrow, v + offset).unwrap();
+        } else {
+            writeln!(dot, "    {} {} {} [{}];", u + offset, arrow, v + offset, attrs.join(", ")).unwrap();
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+#[test]
+fn test_graph_io() {
+    let input = "4 4\n1 2 5\n2 3 1\n1 3 7\n3 4 2\n";
+    let fmt = GraphFormat::new(true, true, false);
+    let (n, edges) = parse_graph(input, fmt);
+    assert_eq!(n, 4);
+    assert_eq!(edges, vec![(0, 1, 5), (1, 2, 1), (0, 2, 7), (2, 3, 2)]);
+    let adj = to_adjacency(n, &edges, fmt.directed);
+    assert_eq!(adj, vec![vec![1, 2], vec![0, 2], vec![1, 0, 3], vec![2]]);
+    let wadj = to_weighted_adjacency(n, &edges, fmt.directed);
+    let mut back = to_edge_list(&wadj, fmt.directed);
+    let mut sorted = edges.iter().map(|&(u, v, w)| (u.min(v), u.max(v), w)).collect::<Vec<_>>();
+    back.sort();
+    sorted.sort();
+    assert_eq!(back, sorted);
+
+    // 0始まり，重みなし，有向．木のように本数の書かれない入力はparse_edgesで読む
+    let mut tokens = "3\n0 1\n0 2\n".split_whitespace();
+    let n = tokens.next().unwrap().parse::<usize>().unwrap();
+    let edges = parse_edges(&mut tokens, n - 1, GraphFormat::new(false, false, true));
+    assert_eq!(edges, vec![(0, 1, 1), (0, 2, 1)]);
+    assert_eq!(to_adjacency(n, &edges, true), vec![vec![1, 2], vec![], vec![]]);
+}
+
+#[test]
+fn test_to_dot() {
+    use crate::graph::dijkstra::{dijkstra, restore_dijkstra};
+
+    let fmt = GraphFormat::new(true, true, false);
+    let (n, edges) = parse_graph("4 4\n1 2 5\n2 3 1\n1 3 7\n3 4 2\n", fmt);
+    let (_, prev) = dijkstra(&to_weighted_adjacency(n, &edges, fmt.directed), 0);
+    let path = restore_dijkstra(&prev, 3);
+    assert_eq!(path, vec![0, 1, 2, 3]);
+    let dot = to_dot(n, &edges, fmt, &path, &[]);
+    let expected = "graph G {
+    1 [color=red, style=filled, fillcolor=mistyrose];
+    2 [color=red, style=filled, fillcolor=mistyrose];
+    3 [color=red, style=filled, fillcolor=mistyrose];
+    4 [color=red, style=filled, fillcolor=mistyrose];
+    1 -- 2 [label=\"5\", color=red, penwidth=2];
+    2 -- 3 [label=\"1\", color=red, penwidth=2];
+    1 -- 3 [label=\"7\"];
+    3 -- 4 [label=\"2\", color=red, penwidth=2];
+}
+";
+    assert_eq!(dot, expected);
+
+    let dot = to_dot(3, &[(0, 1, 1), (1, 2, 1)], GraphFormat::new(false, false, true), &[], &[(1, 2)]);
+    assert_eq!(dot, "digraph G {\n    0;\n    1;\n    2;\n    0 -> 1;\n    1 -> 2 [color=red, penwidth=2];\n}\n");
+}
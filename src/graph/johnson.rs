@@ -0,0 +1,56 @@
+use cargo_snippet::snippet;
+use crate::graph::dijkstra::dijkstra;
+
+/// ジョンソン法
+/// 負の辺を含む疎なグラフの全点対最短経路 O(VE + V(E + V)logV)
+/// ベルマンフォード法で求めたポテンシャルhで辺の重みを w + h[frm] - h[to] >= 0 に直し，各頂点からダイクストラ法を回す
+/// 負閉路があればNone，到達できない組はinf (1 << 60)
+#[snippet("r3yohei_johnson")]
+#[snippet(include = "r3yohei_dijkstra")]
+pub fn johnson(edges: &Vec<Vec<(usize, i64)>>) -> Option<Vec<Vec<i64>>> {
+    let inf = 1 << 60;
+    let n = edges.len();
+    // 全頂点に距離0の仮想始点から辺が張られているとみなしたベルマンフォード法
+    let mut h = vec![0; n];
+    for i in 0..=n {
+        let mut updated = false;
+        for frm in 0..n {
+            for &(to, w) in &edges[frm] {
+                if h[frm] + w < h[to] {
+                    h[to] = h[frm] + w;
+                    updated = true;
+                }
+            }
+        }
+        if !updated { break; }
+        // n回目でも更新されるなら負閉路がある
+        if i == n { return None; }
+    }
+
+    let reweighted = (0..n)
+        .map(|frm| edges[frm].iter().map(|&(to, w)| (to, w + h[frm] - h[to])).collect())
+        .collect::<Vec<Vec<_>>>();
+    let mut dist = vec![vec![inf; n]; n];
+    for s in 0..n {
+        let (d, _) = dijkstra(&reweighted, s);
+        for t in 0..n {
+            if d[t] < inf {
+                dist[s][t] = d[t] - h[s] + h[t];
+            }
+        }
+    }
+
+    Some(dist)
+}
+
+#[test]
+fn test_johnson() {
+    let edges = vec![vec![(1, 3), (2, 8)], vec![(2, -4), (3, 1)], vec![(3, 2)], vec![(0, -1)]];
+    let dist = johnson(&edges).unwrap();
+    assert_eq!(dist[0], vec![0, 3, -1, 1]);
+    assert_eq!(dist[2], vec![1, 4, 0, 2]);
+    assert_eq!(dist[3], vec![-1, 2, -2, 0]);
+
+    let edges = vec![vec![(1, 1)], vec![(0, -2)], vec![]];
+    assert_eq!(johnson(&edges), None);
+}
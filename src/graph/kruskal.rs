@@ -0,0 +1,31 @@
+use cargo_snippet::snippet;
+use crate::data_structure::union_find::UnionFind;
+
+/// クラスカル法により最小全域森のコストと使う辺を返す
+/// 辺は(u, v, w)のリストで与える．非連結なら各連結成分の最小全域木を合わせたものになる
+/// O(ElogE)
+#[snippet("r3yohei_kruskal")]
+#[snippet(include = "r3yohei_UnionFind")]
+pub fn kruskal(n: usize, edges: &Vec<(usize, usize, i64)>) -> (i64, Vec<(usize, usize, i64)>) {
+    let mut sorted = edges.clone();
+    sorted.sort_by_key(|&(_, _, w)| w);
+    let mut uf = UnionFind::new(n);
+    let mut cost = 0;
+    let mut used = vec![];
+    // 重みの小さい辺から，閉路ができないものを採用する
+    for (u, v, w) in sorted {
+        if uf.unite(u, v) {
+            cost += w;
+            used.push((u, v, w));
+        }
+    }
+    (cost, used)
+}
+
+#[test]
+fn test_kruskal() {
+    let edges = vec![(0, 1, 4), (0, 2, 1), (1, 2, 2), (1, 3, 5), (2, 3, 8), (4, 5, 3)];
+    let (cost, used) = kruskal(6, &edges);
+    assert_eq!(cost, 11);
+    assert_eq!(used, vec![(0, 2, 1), (1, 2, 2), (4, 5, 3), (1, 3, 5)]);
+}
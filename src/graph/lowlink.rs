@@ -0,0 +1,178 @@
+use cargo_snippet::snippet;
+
+/// 無向グラフのlowlink
+/// ord[v]: DFSで訪れた順番，low[v]: vから木辺を下り，後退辺を高々1回使って行ける頂点のordの最小値
+/// 非再帰のDFSで，橋･関節点･二重辺連結成分･二重頂点連結成分を求める O(V+E)
+/// 多重辺があってもよい(親への辺は1本だけ飛ばす)
+#[snippet("r3yohei_LowLink")]
+#[derive(Clone, Debug)]
+pub struct LowLink {
+    pub ord: Vec<usize>,
+    pub low: Vec<usize>,
+    /// DFS木の親．根なら!0
+    pub par: Vec<usize>,
+    /// 橋(親, 子)
+    pub bridges: Vec<(usize, usize)>,
+    pub articulation: Vec<usize>,
+    /// 二重頂点連結成分ごとの頂点
+    pub bcc: Vec<Vec<usize>>,
+    // 行きがけ順
+    order: Vec<usize>,
+}
+#[snippet("r3yohei_LowLink")]
+impl LowLink {
+    pub fn new(edges: &Vec<Vec<usize>>) -> Self {
+        let n = edges.len();
+        let mut ll = Self {
+            ord: vec![!0; n],
+            low: vec![!0; n],
+            par: vec![!0; n],
+            bridges: vec![],
+            articulation: vec![],
+            bcc: vec![],
+            order: vec![],
+        };
+        // 二重頂点連結成分を作るための辺のスタック
+        let mut edge_stack = vec![];
+        let mut in_bcc = vec![false; n];
+        for s in 0..n {
+            if ll.ord[s] != !0 { continue; }
+            ll.visit(s, !0);
+            if edges[s].is_empty() {
+                // 孤立点はそれだけで1つの成分
+                ll.bcc.push(vec![s]);
+            }
+            let mut children = 0;
+            // (頂点, 次に見る辺の番号, 親への辺を飛ばしたか)
+            let mut stack = vec![(s, 0, false)];
+            while let Some(&(crt, i, skipped)) = stack.last() {
+                if i < edges[crt].len() {
+                    stack.last_mut().unwrap().1 += 1;
+                    let to = edges[crt][i];
+                    if to == ll.par[crt] && !skipped {
+                        stack.last_mut().unwrap().2 = true;
+                        continue;
+                    }
+                    if ll.ord[to] == !0 {
+                        // 木辺
+                        edge_stack.push((crt, to));
+                        ll.visit(to, crt);
+                        stack.push((to, 0, false));
+                    } else if ll.ord[to] < ll.ord[crt] {
+                        // 後退辺
+                        edge_stack.push((crt, to));
+                        ll.low[crt] = ll.low[crt].min(ll.ord[to]);
+                    }
+                } else {
+                    stack.pop();
+                    let p = ll.par[crt];
+                    if p == !0 { continue; }
+                    ll.low[p] = ll.low[p].min(ll.low[crt]);
+                    if ll.ord[p] < ll.low[crt] {
+                        ll.bridges.push((p, crt));
+                    }
+                    if ll.ord[p] <= ll.low[crt] {
+                        // pを取り除くとcrt側が切り離される
+                        if p == s {
+                            children += 1;
+                        } else {
+                            ll.articulation.push(p);
+                        }
+                        let mut component = vec![];
+                        while let Some((u, v)) = edge_stack.pop() {
+                            for w in [u, v] {
+                                if !in_bcc[w] {
+                                    in_bcc[w] = true;
+                                    component.push(w);
+                                }
+                            }
+                            if (u, v) == (p, crt) { break; }
+                        }
+                        for &w in &component {
+                            in_bcc[w] = false;
+                        }
+                        ll.bcc.push(component);
+                    }
+                }
+            }
+            if children >= 2 {
+                ll.articulation.push(s);
+            }
+        }
+        ll.articulation.sort();
+        ll.articulation.dedup();
+        ll
+    }
+
+    fn visit(&mut self, v: usize, p: usize) {
+        self.ord[v] = self.order.len();
+        self.low[v] = self.order.len();
+        self.par[v] = p;
+        self.order.push(v);
+    }
+
+    /// 二重辺連結成分(橋を取り除いたときの連結成分)
+    /// 成分数と，各頂点の成分番号を返す
+    pub fn two_edge_connected_components(&self) -> (usize, Vec<usize>) {
+        let mut cnt = 0;
+        let mut comp = vec![!0; self.ord.len()];
+        // 行きがけ順に見れば，親の成分番号は決まっている
+        for &v in &self.order {
+            let p = self.par[v];
+            if p == !0 || self.ord[p] < self.low[v] {
+                comp[v] = cnt;
+                cnt += 1;
+            } else {
+                comp[v] = comp[p];
+            }
+        }
+        (cnt, comp)
+    }
+}
+
+#[test]
+fn test_lowlink() {
+    // 0 - 1 - 2 - 0 の三角形に，2 - 3 - 4 - 2 の三角形と 4 - 5 の橋，孤立点6
+    let mut edges = vec![vec![]; 7];
+    for &(u, v) in &[(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 2), (4, 5)] {
+        edges[u].push(v);
+        edges[v].push(u);
+    }
+    let ll = LowLink::new(&edges);
+    assert_eq!(ll.bridges, vec![(4, 5)]);
+    assert_eq!(ll.articulation, vec![2, 4]);
+    let (cnt, comp) = ll.two_edge_connected_components();
+    assert_eq!(cnt, 3);
+    assert_eq!(comp[0], comp[4]);
+    assert_ne!(comp[4], comp[5]);
+    let mut bcc = ll.bcc.clone();
+    for c in bcc.iter_mut() {
+        c.sort();
+    }
+    bcc.sort();
+    assert_eq!(bcc, vec![vec![0, 1, 2], vec![2, 3, 4], vec![4, 5], vec![6]]);
+
+    // 多重辺は橋にならない
+    let edges = vec![vec![1, 1], vec![0, 0, 2], vec![1]];
+    let ll = LowLink::new(&edges);
+    assert_eq!(ll.bridges, vec![(1, 2)]);
+    assert_eq!(ll.articulation, vec![1]);
+}
+
+#[test]
+fn test_lowlink_grid() {
+    use crate::graph::grid::Grid;
+
+    // 2つの部屋が1マスの通路でつながっている
+    let c = vec![
+        "..#..".chars().collect::<Vec<_>>(),
+        ".....".chars().collect::<Vec<_>>(),
+        "..#..".chars().collect::<Vec<_>>(),
+    ];
+    let grid = Grid::new(c);
+    let edges = grid.to_edges(|&c| c == '.');
+    let ll = LowLink::new(&edges);
+    // 壁のマスは孤立点になる
+    assert_eq!(ll.bridges.len(), 2);
+    assert_eq!(ll.articulation, vec![grid.id(1, 1), grid.id(1, 2), grid.id(1, 3)]);
+}
@@ -5,4 +5,19 @@ pub mod dfs;
 pub mod dijkstra;
 pub mod prim;
 pub mod warshall_floyd;
-pub mod min_cost_flow;
\ No newline at end of file
+pub mod min_cost_flow;
+pub mod capacity_scaling_successive_shortest_path;
+pub mod grid;
+pub mod bellman_ford;
+pub mod johnson;
+pub mod kruskal;
+pub mod boruvka;
+pub mod lowlink;
+pub mod scc;
+pub mod two_sat;
+pub mod tree;
+pub mod topological_sort;
+pub mod bipartite;
+pub mod bfs_implicit;
+pub mod astar;
+pub mod io;
\ No newline at end of file
@@ -30,4 +30,44 @@ pub fn prim(edges: &Vec<Vec<(usize, i64)>>) -> i64 {
         }
     }
     cost
-}
\ No newline at end of file
+}
+
+/// プリム法により最小全域森のコストと使う辺(frm, to, w)を返す
+/// 非連結でも，未訪問の頂点から始め直して各連結成分の最小全域木を求める
+/// O(N + MlogN)
+#[snippet("r3yohei_prim_with_edges")]
+pub fn prim_with_edges(edges: &Vec<Vec<(usize, i64)>>) -> (i64, Vec<(usize, usize, i64)>) {
+    let mut cost = 0;
+    let mut used = vec![];
+    let mut visited = vec![false; edges.len()];
+    let mut bh = BinaryHeap::new();
+    for s in 0..edges.len() {
+        if visited[s] {continue;}
+        bh.push((Reverse(0), s, !0));
+        while let Some((Reverse(w1), crt, frm)) = bh.pop() {
+            if visited[crt] {continue;}
+            visited[crt] = true;
+            if frm != !0 {
+                cost += w1;
+                used.push((frm, crt, w1));
+            }
+            for &(to, w2) in &edges[crt] {
+                if visited[to] {continue;}
+                bh.push((Reverse(w2), to, crt));
+            }
+        }
+    }
+    (cost, used)
+}
+
+#[test]
+fn test_prim_with_edges() {
+    let mut edges = vec![vec![]; 6];
+    for &(u, v, w) in &[(0, 1, 4), (0, 2, 1), (1, 2, 2), (1, 3, 5), (2, 3, 8), (4, 5, 3)] {
+        edges[u].push((v, w));
+        edges[v].push((u, w));
+    }
+    let (cost, used) = prim_with_edges(&edges);
+    assert_eq!(cost, 11);
+    assert_eq!(used, vec![(0, 2, 1), (2, 1, 2), (1, 3, 5), (4, 5, 3)]);
+}
@@ -0,0 +1,103 @@
+use cargo_snippet::snippet;
+
+/// 有向グラフの強連結成分分解(非再帰のTarjan法)
+/// 成分数と各頂点の成分番号を返す．成分番号はトポロジカル順(u -> v の辺があれば comp[u] <= comp[v])
+/// O(V+E)
+#[snippet("r3yohei_scc")]
+pub fn scc_ids(edges: &Vec<Vec<usize>>) -> (usize, Vec<usize>) {
+    let n = edges.len();
+    let mut ord = vec![!0; n];
+    let mut low = vec![0; n];
+    let mut comp = vec![!0; n];
+    let mut visited = vec![];
+    let mut k = 0;
+    let mut cnt = 0;
+    for s in 0..n {
+        if ord[s] != !0 { continue; }
+        // (頂点, 次に見る辺の番号)
+        let mut stack = vec![(s, 0)];
+        ord[s] = k;
+        low[s] = k;
+        k += 1;
+        visited.push(s);
+        while let Some(&(crt, i)) = stack.last() {
+            if i < edges[crt].len() {
+                stack.last_mut().unwrap().1 += 1;
+                let to = edges[crt][i];
+                if ord[to] == !0 {
+                    ord[to] = k;
+                    low[to] = k;
+                    k += 1;
+                    visited.push(to);
+                    stack.push((to, 0));
+                } else if comp[to] == !0 {
+                    // まだ成分が決まっていない頂点はスタック上にある
+                    low[crt] = low[crt].min(ord[to]);
+                }
+            } else {
+                stack.pop();
+                if let Some(&(p, _)) = stack.last() {
+                    low[p] = low[p].min(low[crt]);
+                }
+                if low[crt] == ord[crt] {
+                    // crtが成分の根なので，crt以降に訪れた頂点をまとめる
+                    loop {
+                        let v = visited.pop().unwrap();
+                        comp[v] = cnt;
+                        if v == crt { break; }
+                    }
+                    cnt += 1;
+                }
+            }
+        }
+    }
+    // Tarjan法では逆トポロジカル順に見つかるので，反転させる
+    for c in comp.iter_mut() {
+        *c = cnt - 1 - *c;
+    }
+    (cnt, comp)
+}
+
+/// 強連結成分ごとの頂点をトポロジカル順に返す
+#[snippet("r3yohei_scc")]
+pub fn scc(edges: &Vec<Vec<usize>>) -> Vec<Vec<usize>> {
+    let (cnt, comp) = scc_ids(edges);
+    let mut groups = vec![vec![]; cnt];
+    for (v, &c) in comp.iter().enumerate() {
+        groups[c].push(v);
+    }
+    groups
+}
+
+/// 強連結成分を1頂点に潰したDAG(多重辺はまとめる)
+#[snippet("r3yohei_scc")]
+pub fn condensation(edges: &Vec<Vec<usize>>, cnt: usize, comp: &Vec<usize>) -> Vec<Vec<usize>> {
+    let mut dag = vec![vec![]; cnt];
+    for (frm, es) in edges.iter().enumerate() {
+        for &to in es {
+            if comp[frm] != comp[to] {
+                dag[comp[frm]].push(comp[to]);
+            }
+        }
+    }
+    for es in dag.iter_mut() {
+        es.sort();
+        es.dedup();
+    }
+    dag
+}
+
+#[test]
+fn test_scc() {
+    // {0, 1, 2} -> {3, 4} -> {5}
+    let edges = vec![vec![1], vec![2], vec![0, 3], vec![4], vec![3, 5], vec![]];
+    let groups = scc(&edges);
+    assert_eq!(groups, vec![vec![0, 1, 2], vec![3, 4], vec![5]]);
+    let (cnt, comp) = scc_ids(&edges);
+    assert_eq!(condensation(&edges, cnt, &comp), vec![vec![1], vec![2], vec![]]);
+
+    // 長いパスでもスタックオーバーフローしない
+    let n = 1_000_000;
+    let edges = (0..n).map(|i| vec![(i + 1) % n]).collect::<Vec<_>>();
+    assert_eq!(scc_ids(&edges).0, 1);
+}
@@ -0,0 +1,166 @@
+use cargo_snippet::snippet;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+
+/// 有向グラフの閉路を1つ頂点列として返す
+/// 返す頂点列は閉路をたどる順で，最後の頂点から最初の頂点への辺がある
+/// 非再帰DFSで，訪問中(スタック上)の頂点への辺を見つけたら閉路 O(V+E)
+#[snippet("r3yohei_find_cycle")]
+pub fn find_cycle(edges: &Vec<Vec<usize>>) -> Option<Vec<usize>> {
+    let n = edges.len();
+    // 0: 未訪問，1: 訪問中，2: 訪問済み
+    let mut state = vec![0; n];
+    for s in 0..n {
+        if state[s] != 0 { continue; }
+        // (頂点, 次に見る辺の番号)
+        let mut stack = vec![(s, 0)];
+        state[s] = 1;
+        while let Some(&(crt, i)) = stack.last() {
+            if i < edges[crt].len() {
+                stack.last_mut().unwrap().1 += 1;
+                let to = edges[crt][i];
+                if state[to] == 0 {
+                    state[to] = 1;
+                    stack.push((to, 0));
+                } else if state[to] == 1 {
+                    // スタック上のtoからcrtまでが閉路
+                    let start = stack.iter().position(|&(v, _)| v == to).unwrap();
+                    return Some(stack[start..].iter().map(|&(v, _)| v).collect());
+                }
+            } else {
+                state[crt] = 2;
+                stack.pop();
+            }
+        }
+    }
+    None
+}
+
+/// カーン法によるトポロジカルソート O(V+E)
+/// 閉路があればErrでその閉路を返す
+#[snippet("r3yohei_topological_sort")]
+#[snippet(include = "r3yohei_find_cycle")]
+pub fn topological_sort(edges: &Vec<Vec<usize>>) -> Result<Vec<usize>, Vec<usize>> {
+    let n = edges.len();
+    let mut indeg = vec![0; n];
+    for es in edges {
+        for &to in es {
+            indeg[to] += 1;
+        }
+    }
+    // 入次数が0の頂点から順に取り出す
+    let mut deque = (0..n).filter(|&v| indeg[v] == 0).collect::<VecDeque<_>>();
+    let mut order = vec![];
+    while let Some(crt) = deque.pop_front() {
+        order.push(crt);
+        for &to in &edges[crt] {
+            indeg[to] -= 1;
+            if indeg[to] == 0 {
+                deque.push_back(to);
+            }
+        }
+    }
+    if order.len() == n {
+        Ok(order)
+    } else {
+        Err(find_cycle(edges).unwrap())
+    }
+}
+
+/// 辞書順最小のトポロジカルソート O(V+ElogV)
+/// 入次数が0の頂点のうち番号が最小のものを優先度付きキューで取り出す
+/// 閉路があればErrでその閉路を返す
+#[snippet("r3yohei_topological_sort_lex")]
+#[snippet(include = "r3yohei_find_cycle")]
+pub fn topological_sort_lex(edges: &Vec<Vec<usize>>) -> Result<Vec<usize>, Vec<usize>> {
+    let n = edges.len();
+    let mut indeg = vec![0; n];
+    for es in edges {
+        for &to in es {
+            indeg[to] += 1;
+        }
+    }
+    let mut bh = (0..n).filter(|&v| indeg[v] == 0).map(Reverse).collect::<BinaryHeap<_>>();
+    let mut order = vec![];
+    while let Some(Reverse(crt)) = bh.pop() {
+        order.push(crt);
+        for &to in &edges[crt] {
+            indeg[to] -= 1;
+            if indeg[to] == 0 {
+                bh.push(Reverse(to));
+            }
+        }
+    }
+    if order.len() == n {
+        Ok(order)
+    } else {
+        Err(find_cycle(edges).unwrap())
+    }
+}
+
+/// DAG上のsからの最短経路(longestがtrueなら最長経路) O(V+E)
+/// トポロジカル順に緩和するので負の辺があってもよい
+/// 到達できない頂点はinf (最長経路なら-inf)．prevはrestore_bfs, restore_dijkstraでそのまま復元できる
+/// 閉路があればErrでその閉路を返す
+#[snippet("r3yohei_dag_path")]
+#[snippet(include = "r3yohei_topological_sort")]
+pub fn dag_path(edges: &Vec<Vec<(usize, i64)>>, s: usize, longest: bool) -> Result<(Vec<i64>, Vec<usize>), Vec<usize>> {
+    let n = edges.len();
+    let unweighted = edges
+        .iter()
+        .map(|es| es.iter().map(|&(to, _)| to).collect())
+        .collect::<Vec<Vec<_>>>();
+    let order = topological_sort(&unweighted)?;
+    // 最長経路は重みの符号を反転させた最短経路
+    let sign = if longest { -1 } else { 1 };
+    let inf = 1 << 60;
+    let mut dist = vec![inf; n];
+    dist[s] = 0;
+    let mut prev = vec![!0; n];
+    for crt in order {
+        if dist[crt] == inf { continue; }
+        for &(to, w) in &edges[crt] {
+            if dist[crt] + sign * w < dist[to] {
+                dist[to] = dist[crt] + sign * w;
+                prev[to] = crt;
+            }
+        }
+    }
+    for d in dist.iter_mut() {
+        *d *= sign;
+    }
+
+    Ok((dist, prev))
+}
+
+#[test]
+fn test_topological_sort() {
+    let edges = vec![vec![3], vec![0, 3], vec![0], vec![]];
+    assert_eq!(topological_sort(&edges), Ok(vec![1, 2, 0, 3]));
+    assert_eq!(topological_sort_lex(&edges), Ok(vec![1, 2, 0, 3]));
+    let edges = vec![vec![], vec![0], vec![0], vec![1]];
+    assert_eq!(topological_sort(&edges), Ok(vec![2, 3, 1, 0]));
+    assert_eq!(topological_sort_lex(&edges), Ok(vec![2, 3, 1, 0]));
+
+    // 1 -> 2 -> 3 -> 1 が閉路
+    let edges = vec![vec![1], vec![2], vec![3], vec![1, 4], vec![]];
+    assert_eq!(topological_sort(&edges), Err(vec![1, 2, 3]));
+    assert_eq!(find_cycle(&edges), Some(vec![1, 2, 3]));
+    assert_eq!(find_cycle(&vec![vec![1], vec![]]), None);
+}
+
+#[test]
+fn test_dag_path() {
+    use crate::graph::dijkstra::restore_dijkstra;
+
+    let edges = vec![vec![(1, 2), (2, 1)], vec![(3, 1)], vec![(1, 3), (3, 5)], vec![], vec![(3, -1)]];
+    let (dist, prev) = dag_path(&edges, 0, false).unwrap();
+    assert_eq!(dist, vec![0, 2, 1, 3, 1 << 60]);
+    let (dist, prev_longest) = dag_path(&edges, 0, true).unwrap();
+    assert_eq!(dist, vec![0, 4, 1, 6, -(1 << 60)]);
+    // restore_dijkstraで経路を復元できる
+    assert_eq!(restore_dijkstra(&prev, 3), vec![0, 1, 3]);
+    assert_eq!(restore_dijkstra(&prev_longest, 3), vec![0, 2, 3]);
+    let edges = vec![vec![(1, 1)], vec![(0, 1)]];
+    assert_eq!(dag_path(&edges, 0, true), Err(vec![0, 1]));
+}
@@ -0,0 +1,387 @@
+use cargo_snippet::snippet;
+use crate::data_structure::segtree::Min;
+use crate::data_structure::sparse_table::SparseTable;
+use std::collections::VecDeque;
+
+/// 根付き木
+/// 隣接リストか辺リストから，根からのBFSで親･深さ･子を求める
+/// orderはBFS順なので，逆順に回せば葉から根へのDPになる
+#[snippet("r3yohei_RootedTree")]
+#[derive(Clone, Debug)]
+pub struct RootedTree {
+    pub n: usize,
+    pub root: usize,
+    /// 親．根なら!0
+    pub parent: Vec<usize>,
+    pub depth: Vec<usize>,
+    pub children: Vec<Vec<usize>>,
+    pub order: Vec<usize>,
+}
+#[snippet("r3yohei_RootedTree")]
+impl RootedTree {
+    pub fn new(edges: &Vec<Vec<usize>>, root: usize) -> Self {
+        let n = edges.len();
+        let mut parent = vec![!0; n];
+        let mut depth = vec![0; n];
+        let mut children = vec![vec![]; n];
+        let mut order = vec![];
+        let mut visited = vec![false; n];
+        let mut deque = VecDeque::new();
+        deque.push_back(root);
+        visited[root] = true;
+        while let Some(crt) = deque.pop_front() {
+            order.push(crt);
+            for &to in &edges[crt] {
+                if visited[to] { continue; }
+                visited[to] = true;
+                parent[to] = crt;
+                depth[to] = depth[crt] + 1;
+                children[crt].push(to);
+                deque.push_back(to);
+            }
+        }
+        Self { n, root, parent, depth, children, order }
+    }
+
+    /// 辺リスト(u, v)から作る
+    pub fn from_edge_list(n: usize, edge_list: &[(usize, usize)], root: usize) -> Self {
+        let mut edges = vec![vec![]; n];
+        for &(u, v) in edge_list {
+            edges[u].push(v);
+            edges[v].push(u);
+        }
+        Self::new(&edges, root)
+    }
+
+    /// 部分木の大きさ
+    pub fn subtree_size(&self) -> Vec<usize> {
+        let mut size = vec![1; self.n];
+        for &v in self.order.iter().rev() {
+            if self.parent[v] != !0 {
+                size[self.parent[v]] += size[v];
+            }
+        }
+        size
+    }
+
+    /// オイラーツアー
+    /// vの部分木は [tin[v], tout[v]) に並ぶ．tourは行きがけ順に頂点を並べたもの
+    pub fn euler_tour(&self) -> (Vec<usize>, Vec<usize>, Vec<usize>) {
+        let mut tin = vec![0; self.n];
+        let mut tout = vec![0; self.n];
+        let mut tour = vec![];
+        // (頂点, 次に見る子の番号)
+        let mut stack = vec![(self.root, 0)];
+        tin[self.root] = 0;
+        tour.push(self.root);
+        while let Some(&(crt, i)) = stack.last() {
+            if i < self.children[crt].len() {
+                stack.last_mut().unwrap().1 += 1;
+                let to = self.children[crt][i];
+                tin[to] = tour.len();
+                tour.push(to);
+                stack.push((to, 0));
+            } else {
+                tout[crt] = tour.len();
+                stack.pop();
+            }
+        }
+        (tin, tout, tour)
+    }
+}
+
+/// ダブリングによる最小共通祖先(LCA)
+/// 前計算O(NlogN)，クエリO(logN)
+#[snippet("r3yohei_Lca")]
+#[snippet(include = "r3yohei_RootedTree")]
+#[derive(Clone, Debug)]
+pub struct Lca {
+    // up[k][v]: vの2^k個上の祖先(なければ!0)
+    up: Vec<Vec<usize>>,
+    depth: Vec<usize>,
+}
+#[snippet("r3yohei_Lca")]
+impl Lca {
+    pub fn new(tree: &RootedTree) -> Self {
+        let mut log = 1;
+        while (1 << log) < tree.n {
+            log += 1;
+        }
+        let mut up = vec![tree.parent.clone()];
+        for k in 1..log {
+            let next = (0..tree.n)
+                .map(|v| if up[k - 1][v] == !0 { !0 } else { up[k - 1][up[k - 1][v]] })
+                .collect();
+            up.push(next);
+        }
+        Self { up, depth: tree.depth.clone() }
+    }
+
+    /// vのk個上の祖先(なければ!0)
+    pub fn kth_ancestor(&self, mut v: usize, k: usize) -> usize {
+        if k > self.depth[v] {
+            return !0;
+        }
+        for i in 0..self.up.len() {
+            if k >> i & 1 == 1 {
+                v = self.up[i][v];
+            }
+        }
+        v
+    }
+
+    pub fn lca(&self, u: usize, v: usize) -> usize {
+        let (mut u, mut v) = if self.depth[u] < self.depth[v] { (v, u) } else { (u, v) };
+        // 深さを揃える
+        u = self.kth_ancestor(u, self.depth[u] - self.depth[v]);
+        if u == v {
+            return u;
+        }
+        // 祖先が一致しない限り上る
+        for k in (0..self.up.len()).rev() {
+            if self.up[k][u] != self.up[k][v] {
+                u = self.up[k][u];
+                v = self.up[k][v];
+            }
+        }
+        self.up[0][u]
+    }
+
+    /// uとvの間の辺の本数
+    pub fn dist(&self, u: usize, v: usize) -> usize {
+        self.depth[u] + self.depth[v] - 2 * self.depth[self.lca(u, v)]
+    }
+}
+
+/// オイラーツアーとスパーステーブルによる最小共通祖先(LCA)
+/// 行きと帰りに頂点を並べた長さ2N-1の列で，区間の深さ最小の頂点がLCAになる
+/// 前計算O(NlogN)，クエリO(1)
+#[snippet("r3yohei_EulerLca")]
+#[snippet(include = "r3yohei_RootedTree")]
+#[snippet(include = "r3yohei_SparseTable")]
+pub struct EulerLca {
+    n: usize,
+    // 頂点vが最初に現れる位置
+    first: Vec<usize>,
+    depth: Vec<usize>,
+    // 深さ * n + 頂点 の最小値を求める
+    st: SparseTable<Min<usize>>,
+}
+#[snippet("r3yohei_EulerLca")]
+impl EulerLca {
+    pub fn new(tree: &RootedTree) -> Self {
+        let n = tree.n;
+        let mut first = vec![0; n];
+        let mut tour = vec![];
+        // (頂点, 次に見る子の番号)
+        let mut stack = vec![(tree.root, 0)];
+        while let Some(&(crt, i)) = stack.last() {
+            if i == 0 {
+                first[crt] = tour.len();
+            }
+            tour.push(tree.depth[crt] * n + crt);
+            if i < tree.children[crt].len() {
+                stack.last_mut().unwrap().1 += 1;
+                stack.push((tree.children[crt][i], 0));
+            } else {
+                stack.pop();
+            }
+        }
+        Self { n, first, depth: tree.depth.clone(), st: SparseTable::new(&tour) }
+    }
+
+    pub fn lca(&self, u: usize, v: usize) -> usize {
+        let (l, r) = if self.first[u] < self.first[v] { (self.first[u], self.first[v]) } else { (self.first[v], self.first[u]) };
+        self.st.prod(l, r + 1) % self.n
+    }
+
+    /// uとvの間の辺の本数
+    pub fn dist(&self, u: usize, v: usize) -> usize {
+        self.depth[u] + self.depth[v] - 2 * self.depth[self.lca(u, v)]
+    }
+}
+
+/// HL分解(重軽分解)
+/// 各頂点を最も大きい子とつないだheavy pathに分け，heavy pathが連続するように番号posを振る
+/// 任意のパスはO(logN)個の区間になるので，セグメント木などと組み合わせてパスクエリを処理する
+#[snippet("r3yohei_Hld")]
+#[snippet(include = "r3yohei_RootedTree")]
+#[derive(Clone, Debug)]
+pub struct Hld {
+    parent: Vec<usize>,
+    depth: Vec<usize>,
+    /// vが属するheavy pathの先頭
+    pub head: Vec<usize>,
+    /// vの番号．vの部分木は [pos[v], pos[v] + size[v]) になる
+    pub pos: Vec<usize>,
+    pub size: Vec<usize>,
+}
+#[snippet("r3yohei_Hld")]
+impl Hld {
+    pub fn new(tree: &RootedTree) -> Self {
+        let size = tree.subtree_size();
+        // 子のうち部分木が最も大きいもの(heavy child)を最初に訪れる
+        let mut children = tree.children.clone();
+        for cs in children.iter_mut() {
+            cs.sort_by_key(|&c| std::cmp::Reverse(size[c]));
+        }
+        let mut head = vec![tree.root; tree.n];
+        let mut pos = vec![0; tree.n];
+        let mut stack = vec![tree.root];
+        let mut k = 0;
+        while let Some(crt) = stack.pop() {
+            pos[crt] = k;
+            k += 1;
+            for (i, &c) in children[crt].iter().enumerate().rev() {
+                head[c] = if i == 0 { head[crt] } else { c };
+                stack.push(c);
+            }
+        }
+        Self {
+            parent: tree.parent.clone(),
+            depth: tree.depth.clone(),
+            head,
+            pos,
+            size,
+        }
+    }
+
+    pub fn lca(&self, mut u: usize, mut v: usize) -> usize {
+        while self.head[u] != self.head[v] {
+            if self.depth[self.head[u]] < self.depth[self.head[v]] {
+                std::mem::swap(&mut u, &mut v);
+            }
+            u = self.parent[self.head[u]];
+        }
+        if self.depth[u] < self.depth[v] { u } else { v }
+    }
+
+    /// u-v パス上の頂点をposの半開区間の列に分解する
+    /// edgeがtrueなら，辺(子のposに載せる)のクエリとしてLCAを含めない
+    pub fn path(&self, mut u: usize, mut v: usize, edge: bool) -> Vec<(usize, usize)> {
+        let mut ranges = vec![];
+        while self.head[u] != self.head[v] {
+            if self.depth[self.head[u]] < self.depth[self.head[v]] {
+                std::mem::swap(&mut u, &mut v);
+            }
+            ranges.push((self.pos[self.head[u]], self.pos[u] + 1));
+            u = self.parent[self.head[u]];
+        }
+        let (l, r) = if self.pos[u] < self.pos[v] { (u, v) } else { (v, u) };
+        let offset = if edge { 1 } else { 0 };
+        if self.pos[l] + offset <= self.pos[r] {
+            ranges.push((self.pos[l] + offset, self.pos[r] + 1));
+        }
+        ranges
+    }
+
+    /// vの部分木に対応する区間
+    pub fn subtree(&self, v: usize) -> (usize, usize) {
+        (self.pos[v], self.pos[v] + self.size[v])
+    }
+}
+
+/// 全方位木DP
+/// 部分木の値をmergeでまとめ，add_root(まとめた値, v)でvを根とする部分木の値にする
+/// eはmergeの単位元．全頂点について，そこを根としたときの値を返す O(N)
+#[snippet("r3yohei_rerooting")]
+#[snippet(include = "r3yohei_RootedTree")]
+pub fn rerooting<T: Clone>(
+    tree: &RootedTree,
+    e: T,
+    merge: impl Fn(&T, &T) -> T,
+    add_root: impl Fn(&T, usize) -> T,
+) -> Vec<T> {
+    let n = tree.n;
+    // down[v]: 元の根で見たときの，vの部分木の値
+    let mut down = vec![e.clone(); n];
+    for &v in tree.order.iter().rev() {
+        let mut acc = e.clone();
+        for &c in &tree.children[v] {
+            acc = merge(&acc, &down[c]);
+        }
+        down[v] = add_root(&acc, v);
+    }
+    // up[v]: vを根としたときの，親側の部分木の値
+    let mut up = vec![e.clone(); n];
+    let mut ans = vec![e.clone(); n];
+    for &v in &tree.order {
+        let cs = &tree.children[v];
+        // 子を1つ除いたものをまとめるために，累積をとる
+        let mut suffix = vec![e.clone(); cs.len() + 1];
+        for i in (0..cs.len()).rev() {
+            suffix[i] = merge(&down[cs[i]], &suffix[i + 1]);
+        }
+        let mut prefix = if v == tree.root { e.clone() } else { up[v].clone() };
+        ans[v] = add_root(&merge(&prefix, &suffix[0]), v);
+        for (i, &c) in cs.iter().enumerate() {
+            up[c] = add_root(&merge(&prefix, &suffix[i + 1]), v);
+            prefix = merge(&prefix, &down[c]);
+        }
+    }
+    ans
+}
+
+#[test]
+fn test_tree() {
+    //       0
+    //     / | \
+    //    1  2  3
+    //   / \     \
+    //  4   5     6
+    //      |
+    //      7
+    let tree = RootedTree::from_edge_list(8, &[(0, 1), (0, 2), (0, 3), (1, 4), (1, 5), (3, 6), (5, 7)], 0);
+    assert_eq!(tree.parent, vec![!0, 0, 0, 0, 1, 1, 3, 5]);
+    assert_eq!(tree.depth, vec![0, 1, 1, 1, 2, 2, 2, 3]);
+
+    let (tin, tout, tour) = tree.euler_tour();
+    assert_eq!(tour, vec![0, 1, 4, 5, 7, 2, 3, 6]);
+    assert_eq!((tin[1], tout[1]), (1, 5));
+    assert_eq!((tin[6], tout[6]), (7, 8));
+
+    let lca = Lca::new(&tree);
+    let hld = Hld::new(&tree);
+    let euler = EulerLca::new(&tree);
+    for &(u, v, w) in &[(4, 7, 1), (7, 6, 0), (2, 2, 2), (5, 7, 5), (4, 1, 1)] {
+        assert_eq!(lca.lca(u, v), w);
+        assert_eq!(hld.lca(u, v), w);
+        assert_eq!(euler.lca(u, v), w);
+    }
+    assert_eq!(lca.dist(7, 6), 5);
+    assert_eq!(euler.dist(7, 6), 5);
+    assert_eq!(lca.kth_ancestor(7, 2), 1);
+    assert_eq!(lca.kth_ancestor(7, 4), !0);
+
+    // パス上の頂点数は区間の長さの和になる
+    let len = |ranges: Vec<(usize, usize)>| ranges.iter().map(|&(l, r)| r - l).sum::<usize>();
+    assert_eq!(len(hld.path(7, 6, false)), 6);
+    assert_eq!(len(hld.path(7, 6, true)), 5);
+    assert_eq!(len(hld.path(4, 4, true)), 0);
+    assert_eq!(hld.subtree(1).1 - hld.subtree(1).0, 4);
+
+    // 全方位木DPで，各頂点から他の全頂点への距離の和を求める
+    // 値は(距離の和, 頂点数)
+    let ans = rerooting(&tree, (0, 0), |a, b| (a.0 + b.0, a.1 + b.1), |a, _| (a.0 + a.1, a.1 + 1));
+    for (v, &a) in ans.iter().enumerate() {
+        let expected = (0..tree.n).map(|u| lca.dist(u, v)).sum::<usize>();
+        assert_eq!(a, (expected, tree.n));
+    }
+}
+
+#[test]
+fn test_euler_lca_random() {
+    use crate::rand::xoshiro256::Xoshiro256;
+
+    let mut rng = Xoshiro256::new(8192);
+    for n in [1, 2, 10, 200] {
+        let edge_list = (1..n).map(|v| (rng.gen_usize(0, v), v)).collect::<Vec<_>>();
+        let tree = RootedTree::from_edge_list(n, &edge_list, rng.gen_usize(0, n));
+        let lca = Lca::new(&tree);
+        let euler = EulerLca::new(&tree);
+        for _ in 0..500 {
+            let (u, v) = (rng.gen_usize(0, n), rng.gen_usize(0, n));
+            assert_eq!(euler.lca(u, v), lca.lca(u, v));
+        }
+    }
+}
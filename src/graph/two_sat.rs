@@ -0,0 +1,70 @@
+use cargo_snippet::snippet;
+use crate::graph::scc::scc_ids;
+
+/// 2-SAT
+/// 変数x_iが真である頂点を2i，偽である頂点を2i+1とした含意グラフを強連結成分分解する O(N+M)
+#[snippet("r3yohei_TwoSat")]
+#[snippet(include = "r3yohei_scc")]
+#[derive(Clone, Debug)]
+pub struct TwoSat {
+    n: usize,
+    edges: Vec<Vec<usize>>,
+    answer: Vec<bool>,
+}
+#[snippet("r3yohei_TwoSat")]
+impl TwoSat {
+    pub fn new(n: usize) -> Self {
+        Self {
+            n,
+            edges: vec![vec![]; 2 * n],
+            answer: vec![false; n],
+        }
+    }
+
+    /// (x_i == f) または (x_j == g) という節を追加する
+    pub fn add_clause(&mut self, i: usize, f: bool, j: usize, g: bool) {
+        let id = |k: usize, b: bool| 2 * k + if b { 0 } else { 1 };
+        // ¬(x_i == f) => (x_j == g)，¬(x_j == g) => (x_i == f)
+        self.edges[id(i, !f)].push(id(j, g));
+        self.edges[id(j, !g)].push(id(i, f));
+    }
+
+    /// 充足可能か判定し，可能なら割り当てをanswer()で取れるようにする
+    pub fn satisfiable(&mut self) -> bool {
+        let (_, comp) = scc_ids(&self.edges);
+        for i in 0..self.n {
+            if comp[2 * i] == comp[2 * i + 1] {
+                return false;
+            }
+            // トポロジカル順で後ろにあるほうを真にする
+            self.answer[i] = comp[2 * i] > comp[2 * i + 1];
+        }
+        true
+    }
+
+    pub fn answer(&self) -> &Vec<bool> {
+        &self.answer
+    }
+}
+
+#[test]
+fn test_two_sat() {
+    // (x0 ∨ x1) ∧ (¬x0 ∨ x2) ∧ (¬x1 ∨ ¬x2) ∧ (¬x2 ∨ ¬x2)
+    let clauses = vec![(0, true, 1, true), (0, false, 2, true), (1, false, 2, false), (2, false, 2, false)];
+    let mut ts = TwoSat::new(3);
+    for &(i, f, j, g) in &clauses {
+        ts.add_clause(i, f, j, g);
+    }
+    assert!(ts.satisfiable());
+    let ans = ts.answer();
+    for &(i, f, j, g) in &clauses {
+        assert!(ans[i] == f || ans[j] == g);
+    }
+    assert_eq!(ans, &vec![false, true, false]);
+
+    // x0 と ¬x0 を両方要求すると充足できない
+    let mut ts = TwoSat::new(1);
+    ts.add_clause(0, true, 0, true);
+    ts.add_clause(0, false, 0, false);
+    assert!(!ts.satisfiable());
+}
@@ -17,4 +17,137 @@ fn warshall_floyd(dist: &mut Vec<Vec<i64>>) {
             }
         }
     }
-}
\ No newline at end of file
+}
+
+/// 経路復元･負閉路検出･辺の逐次追加に対応したワーシャルフロイド法
+/// 到達できない組はi64::MAXで持ち，足し算がオーバーフローしないようにする
+/// next[i][j]: iからjへの最短経路でiの次に通る頂点
+#[snippet("r3yohei_WarshallFloyd")]
+pub struct WarshallFloyd {
+    n: usize,
+    dist: Vec<Vec<i64>>,
+    next: Vec<Vec<usize>>,
+}
+#[snippet("r3yohei_WarshallFloyd")]
+impl WarshallFloyd {
+    const INF: i64 = i64::MAX;
+
+    /// 隣接リストから全点対最短経路を求める O(V^3)
+    pub fn new(edges: &Vec<Vec<(usize, i64)>>) -> Self {
+        let n = edges.len();
+        let mut dist = vec![vec![Self::INF; n]; n];
+        let mut next = vec![vec![!0; n]; n];
+        for i in 0..n {
+            dist[i][i] = 0;
+            next[i][i] = i;
+        }
+        for (frm, es) in edges.iter().enumerate() {
+            for &(to, w) in es {
+                if w < dist[frm][to] {
+                    dist[frm][to] = w;
+                    next[frm][to] = to;
+                }
+            }
+        }
+        for k in 0..n {
+            for i in 0..n {
+                if dist[i][k] == Self::INF { continue; }
+                for j in 0..n {
+                    if dist[k][j] == Self::INF { continue; }
+                    // 負閉路があると値がいくらでも小さくなるので，飽和させる
+                    let d = dist[i][k].saturating_add(dist[k][j]);
+                    if d < dist[i][j] {
+                        dist[i][j] = d;
+                        next[i][j] = next[i][k];
+                    }
+                }
+            }
+        }
+        Self { n, dist, next }
+    }
+
+    /// uからvへの最短距離
+    /// 到達できなければNone．負閉路を経由できるならis_neg_inf(u, v)がtrueで，値に意味はない
+    pub fn dist(&self, u: usize, v: usize) -> Option<i64> {
+        if self.dist[u][v] == Self::INF {
+            None
+        } else {
+            Some(self.dist[u][v])
+        }
+    }
+
+    /// vが負閉路上にあるか
+    pub fn on_negative_cycle(&self, v: usize) -> bool {
+        self.dist[v][v] < 0
+    }
+
+    /// uからvへの経路に負閉路が絡み，距離が-∞になるか O(V)
+    pub fn is_neg_inf(&self, u: usize, v: usize) -> bool {
+        (0..self.n).any(|k| {
+            self.on_negative_cycle(k) && self.dist[u][k] != Self::INF && self.dist[k][v] != Self::INF
+        })
+    }
+
+    /// uからvへの最短経路の頂点列
+    /// 到達できないか，距離が-∞ならNone
+    pub fn path(&self, u: usize, v: usize) -> Option<Vec<usize>> {
+        if self.dist[u][v] == Self::INF || self.is_neg_inf(u, v) {
+            return None;
+        }
+        let mut path = vec![u];
+        let mut crt = u;
+        while crt != v {
+            crt = self.next[crt][v];
+            path.push(crt);
+        }
+        Some(path)
+    }
+
+    /// 重みwの辺u->vを追加して最短距離を更新する O(V^2)
+    /// 追加後も負閉路がないことを仮定する
+    pub fn add_edge(&mut self, u: usize, v: usize, w: i64) {
+        if w >= self.dist[u][v] { return; }
+        // 更新中に書き換わらないよう，uへの距離とvからの距離を控えておく
+        let to_u = (0..self.n).map(|i| self.dist[i][u]).collect::<Vec<_>>();
+        let from_v = self.dist[v].clone();
+        for i in 0..self.n {
+            if to_u[i] == Self::INF { continue; }
+            let next_i = if i == u { v } else { self.next[i][u] };
+            #[allow(clippy::needless_range_loop)]
+            for j in 0..self.n {
+                if from_v[j] == Self::INF { continue; }
+                let d = to_u[i].saturating_add(w).saturating_add(from_v[j]);
+                if d < self.dist[i][j] {
+                    self.dist[i][j] = d;
+                    self.next[i][j] = next_i;
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_warshall_floyd_with_path() {
+    let edges = vec![vec![(1, 5)], vec![(2, 2)], vec![(3, 2)], vec![]];
+    let mut wf = WarshallFloyd::new(&edges);
+    assert_eq!(wf.dist(0, 3), Some(9));
+    assert_eq!(wf.dist(3, 0), None);
+    assert_eq!(wf.path(0, 3), Some(vec![0, 1, 2, 3]));
+
+    // 辺を1本ずつ追加する
+    wf.add_edge(0, 2, 3);
+    assert_eq!(wf.dist(0, 3), Some(5));
+    assert_eq!(wf.path(0, 3), Some(vec![0, 2, 3]));
+    wf.add_edge(3, 0, 1);
+    assert_eq!(wf.dist(2, 1), Some(8));
+    assert_eq!(wf.path(2, 1), Some(vec![2, 3, 0, 1]));
+
+    // 1 -> 2 -> 1 が負閉路
+    let edges = vec![vec![(1, 1)], vec![(2, -3)], vec![(1, 1), (3, 1)], vec![]];
+    let wf = WarshallFloyd::new(&edges);
+    assert!(!wf.on_negative_cycle(0));
+    assert!(wf.on_negative_cycle(1));
+    assert!(wf.is_neg_inf(0, 3));
+    assert_eq!(wf.path(0, 3), None);
+    assert!(!wf.is_neg_inf(3, 3));
+}
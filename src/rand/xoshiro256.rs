@@ -22,13 +22,13 @@ impl SplitMix64 {
 /// Xoshiro256による乱数生成器
 #[snippet("r3yohei_Xoshiro256")]
 #[snippet(include = "r3yohei_split_mix_64")]
-struct Xoshiro256 {
+pub struct Xoshiro256 {
     s: [u64; 4],
 }
 #[snippet("r3yohei_Xoshiro256")]
 #[snippet(include = "r3yohei_split_mix_64")]
 impl Xoshiro256 {
-    fn new(seed: u64) -> Self {
+    pub fn new(seed: u64) -> Self {
         let mut split_mix_64 = SplitMix64::new(seed);
         let mut s = [0; 4];
         for si in &mut s {
@@ -37,7 +37,7 @@ impl Xoshiro256 {
         Self { s }
     }
 
-    fn next_u64(&mut self) -> u64 {
+    pub fn next_u64(&mut self) -> u64 {
         let result = (self.s[1].wrapping_mul(5)).rotate_left(7).wrapping_mul(9);
         let t = self.s[1] << 17;
 
@@ -51,19 +51,19 @@ impl Xoshiro256 {
         result
     }
 
-    fn gen_usize(&mut self, lower: usize, upper: usize) -> usize {
+    pub fn gen_usize(&mut self, lower: usize, upper: usize) -> usize {
         assert!(lower < upper);
         let count = upper - lower;
         (self.next_u64() % count as u64) as usize + lower
     }
 
-    fn gen_i64(&mut self, lower: i64, upper: i64) -> i64 {
+    pub fn gen_i64(&mut self, lower: i64, upper: i64) -> i64 {
         assert!(lower < upper);
         let count = upper - lower;
         (self.next_u64() % count as u64) as i64 + lower
     }
 
-    fn gen_f64(&mut self) -> f64 {
+    pub fn gen_f64(&mut self) -> f64 {
         const UPPER_MASK: u64 = 0x3ff0000000000000;
         const LOWER_MASK: u64 = 0xfffffffffffff;
         let result = UPPER_MASK | (self.next_u64() & LOWER_MASK);
@@ -71,11 +71,11 @@ impl Xoshiro256 {
         result - 1.0
     }
 
-    fn gen_bool(&mut self, prob: f64) -> bool {
+    pub fn gen_bool(&mut self, prob: f64) -> bool {
         self.gen_f64() < prob
     }
 
-    fn fisher_yates_shuffle<T>(&mut self, items: &mut [T]) {
+    pub fn fisher_yates_shuffle<T>(&mut self, items: &mut [T]) {
         for i in (1..items.len()).rev() {
             let j = (self.next_u64() as usize) % (i + 1);
             items.swap(j, i);
@@ -2,25 +2,25 @@ use cargo_snippet::snippet;
 
 #[snippet("r3yohei_ModInt")]
 #[derive(Debug, Clone, Copy, Default)]
-struct ModInt {
+pub struct ModInt {
     value: usize,
 }
 #[snippet("r3yohei_ModInt")]
 impl ModInt {
-    const MOD: usize = 998244353;
-    fn new(n: usize) -> Self {
+    pub const MOD: usize = 998244353;
+    pub fn new(n: usize) -> Self {
         ModInt { value: n % Self::MOD }
     }
-    fn zero() -> Self {
+    pub fn zero() -> Self {
         ModInt { value: 0 }
     }
-    fn one() -> Self {
+    pub fn one() -> Self {
         ModInt { value: 1 }
     }
-    fn value(&self) -> usize {
+    pub fn value(&self) -> usize {
         self.value
     }
-    fn pow(&self, n: usize) -> Self {
+    pub fn pow(&self, n: usize) -> Self {
         let mut p = *self;
         let mut ret = ModInt::one();
         let mut nn = n;
@@ -33,7 +33,7 @@ impl ModInt {
         }
         ret
     }
-    fn inv(&self) -> Self {
+    pub fn inv(&self) -> Self {
         fn ext_gcd(a: usize, b: usize) -> (isize, isize, usize) {
             if a == 0 {
                 return (0, 1, b);